@@ -0,0 +1,925 @@
+use std::fmt;
+use crate::game::board::*;
+use crate::game::development::{DevelopmentCard, HeldCard};
+use crate::game::resources::{PlayerResourceCount, ResourceCount, CITY_COST, DEVELOPMENT_COST, RESOURCES, ROAD_COST, SETTLEMENT_COST};
+use crate::game::trade::TradeOffer;
+use crate::game::turn::*;
+
+/// An enumeration of the moves a player can make on the board.
+///
+/// Every action carries the player performing it, so an action can be validated
+/// and applied on its own without any extra context.
+///
+/// - `BuildRoad`: Builds a road on the given path.
+/// - `BuildSettlement`: Builds a settlement on the given intersection.
+/// - `BuildCity`: Upgrades the player's settlement on the given intersection to a city.
+/// - `RollDice`: Rolls the dice at the start of the turn.
+/// - `Discard`: Discards the given cards after a 7 was rolled.
+/// - `MoveRobber`: Moves the robber to another tile and steals a random card from `victim`, an
+///   opponent with a building on that tile. `victim` may only be `None` if no such opponent holds any card.
+/// - `BuyDevelopmentCard`: Buys the top card of the development deck.
+/// - `PlayKnight`: Plays a knight card and moves the robber.
+/// - `PlayRoadBuilding`: Plays a road building card and builds two free roads.
+/// - `PlayYearOfPlenty`: Plays a year of plenty card and takes the two given resources from the bank.
+/// - `PlayMonopoly`: Plays a monopoly card and takes every card of the given resource from the other players.
+/// - `TradeWithBank`: Gives the bank cards of `give` at the player's trade ratio for one card of `get`.
+/// - `OfferTrade`: Offers a trade to the other players; the offer must come from the current player.
+/// - `AcceptTrade`: Accepts the pending trade offer, exchanging the cards.
+/// - `RejectTrade`: Rejects the pending trade offer, or withdraws it if the player made it.
+/// - `CounterTrade`: Rejects the pending offer and makes a counter offer to the current player.
+/// - `EndTurn`: Ends the turn of the player.
+///
+/// Example usage:
+/// ```no_run
+/// let action = Action::BuildRoad { player: Player::Red, path: PathId(13) };
+/// let event = game.apply(action)?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Action {
+    BuildRoad { player: Player, path: PathId },
+    BuildSettlement { player: Player, intersection: IntersectionId },
+    BuildCity { player: Player, intersection: IntersectionId },
+    RollDice { player: Player },
+    Discard { player: Player, cards: ResourceCount },
+    MoveRobber { player: Player, robber: RobberId, victim: Option<Player> },
+    BuyDevelopmentCard { player: Player },
+    PlayKnight { player: Player },
+    PlayRoadBuilding { player: Player },
+    PlayYearOfPlenty { player: Player, first: TileKind, second: TileKind },
+    PlayMonopoly { player: Player, resource: TileKind },
+    TradeWithBank { player: Player, give: TileKind, get: TileKind },
+    OfferTrade { offer: TradeOffer },
+    AcceptTrade { player: Player },
+    RejectTrade { player: Player },
+    CounterTrade { offer: TradeOffer },
+    EndTurn { player: Player },
+}
+
+impl Action {
+    /// Returns the player performing the action.
+    pub fn player(&self) -> Player {
+        match *self {
+            Action::BuildRoad { player, .. } => player,
+            Action::BuildSettlement { player, .. } => player,
+            Action::BuildCity { player, .. } => player,
+            Action::RollDice { player } => player,
+            Action::Discard { player, .. } => player,
+            Action::MoveRobber { player, .. } => player,
+            Action::BuyDevelopmentCard { player } => player,
+            Action::PlayKnight { player } => player,
+            Action::PlayRoadBuilding { player } => player,
+            Action::PlayYearOfPlenty { player, .. } => player,
+            Action::PlayMonopoly { player, .. } => player,
+            Action::TradeWithBank { player, .. } => player,
+            Action::OfferTrade { offer } => offer.from,
+            Action::AcceptTrade { player } => player,
+            Action::RejectTrade { player } => player,
+            Action::CounterTrade { offer } => offer.from,
+            Action::EndTurn { player } => player,
+        }
+    }
+}
+
+/// An enumeration of what happened on the board after an `Action` was applied.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    RoadBuilt { player: Player, path: PathId },
+    SettlementBuilt { player: Player, intersection: IntersectionId },
+    CityBuilt { player: Player, intersection: IntersectionId },
+    DiceRolled { player: Player, roll: u8, produced: PlayerResourceCount },
+    Discarded { player: Player, cards: ResourceCount },
+    RobberMoved { player: Player, robber: RobberId, victim: Option<Player>, stolen: Option<TileKind> },
+    DevelopmentCardBought { player: Player, card: DevelopmentCard },
+    DevelopmentCardPlayed { player: Player, card: DevelopmentCard },
+    TradedWithBank { player: Player, gave: ResourceCount, received: ResourceCount },
+    TradeOffered { offer: TradeOffer },
+    Traded { offer: TradeOffer, with: Player },
+    TradeRejected { player: Player },
+    TurnEnded { player: Player },
+}
+
+/// An enumeration of the reasons an `Action` can be rejected by `Game::apply`.
+///
+/// - `NotYourTurn`: The action is performed by a player who is not allowed to act now.
+/// - `WrongPhase`: The action is not allowed in the current phase of the turn.
+/// - `GameOver`: The game has ended.
+/// - `NotEnoughResources`: The player cannot pay the cost of the action.
+/// - `UnknownPath`, `UnknownIntersection`, `UnknownTile`: The action refers to a location outside the board.
+/// - `IllegalRoad`: The path is occupied or not connected to the player's network.
+/// - `IllegalSettlement`: The intersection is occupied, too close to a building or not connected to the player's roads.
+/// - `IllegalCity`: The intersection does not hold a settlement of the player.
+/// - `InvalidDiscard`: The discarded cards are not exactly half of the player's hand.
+/// - `RobberNotMoved`: The robber must be moved to a different tile.
+/// - `IllegalVictim`: The victim has no building on the robber's tile, or a victim must be chosen.
+/// - `DevelopmentDeckEmpty`: There are no development cards left to buy.
+/// - `CardNotPlayable`: The player holds no such card bought before this turn, or already played a card this turn.
+/// - `NotEnoughInBank`: The bank cannot give the requested resources.
+/// - `UnknownResource`: The action names a tile kind that is not a resource.
+/// - `NoPiecesLeft`: The player has no piece of this kind left to place.
+/// - `IllegalTrade`: A trade gives or gets nothing, gives and gets the same resource, or does not involve the current player.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    NotYourTurn,
+    WrongPhase,
+    GameOver,
+    NotEnoughResources,
+    UnknownPath,
+    UnknownIntersection,
+    UnknownTile,
+    IllegalRoad,
+    IllegalSettlement,
+    IllegalCity,
+    InvalidDiscard,
+    RobberNotMoved,
+    IllegalVictim,
+    DevelopmentDeckEmpty,
+    CardNotPlayable,
+    NotEnoughInBank,
+    UnknownResource,
+    IllegalTrade,
+    NoPiecesLeft,
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            RuleError::NotYourTurn => "it is not the turn of this player",
+            RuleError::WrongPhase => "this action is not allowed in the current phase",
+            RuleError::GameOver => "the game is over",
+            RuleError::NotEnoughResources => "not enough resources",
+            RuleError::UnknownPath => "unknown path",
+            RuleError::UnknownIntersection => "unknown intersection",
+            RuleError::UnknownTile => "unknown tile",
+            RuleError::IllegalRoad => "a road cannot be built on this path",
+            RuleError::IllegalSettlement => "a settlement cannot be built on this intersection",
+            RuleError::IllegalCity => "a city cannot be built on this intersection",
+            RuleError::InvalidDiscard => "exactly half of the cards in hand must be discarded",
+            RuleError::RobberNotMoved => "the robber must be moved to a different tile",
+            RuleError::IllegalVictim => "this player cannot be robbed from this tile",
+            RuleError::DevelopmentDeckEmpty => "there are no development cards left",
+            RuleError::CardNotPlayable => "this development card cannot be played now",
+            RuleError::NotEnoughInBank => "the bank does not hold these resources",
+            RuleError::UnknownResource => "this tile kind is not a resource",
+            RuleError::IllegalTrade => "a trade must exchange different resources",
+            RuleError::NoPiecesLeft => "no piece of this kind is left",
+        };
+        write!(f, "{}", message)
+    }
+}
+
+impl Game {
+    /// Validates an action against the rules and applies it to the game state.
+    ///
+    /// The action must be allowed in the current `Phase` of the turn and be performed by
+    /// the player whose turn it is (or, while discarding, by a player who still has to discard).
+    /// Building actions check the placement against `possible_road_paths`, `legal_settlements` and
+    /// `legal_cities` and deduct the cost from the player's resources;
+    /// placements in the setup phase are free. The game is over as soon as the current
+    /// player reaches the victory target. The second settlement of the setup phase collects
+    /// one card of every producing tile around it.
+    ///
+    /// # Arguments
+    /// - `action`: The action to apply.
+    ///
+    /// # Returns
+    /// The `Event` describing the change, or a `RuleError` if the action is not allowed.
+    /// A rejected action leaves the state untouched.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// match game.apply(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }) {
+    ///     Ok(event) => println!("{:?}", event),
+    ///     Err(error) => println!("Illegal move: {}", error),
+    /// }
+    /// ```
+    pub fn apply(&mut self, action: Action) -> Result<Event, RuleError> {
+        self.check_phase(&action)?;
+        let event = self.apply_unchecked(action)?;
+        if self.winner().is_some() {
+            self.state.turn.phase = Phase::GameOver;
+        }
+        Ok(event)
+    }
+
+    /// Applies an action that is allowed in the current phase.
+    fn apply_unchecked(&mut self, action: Action) -> Result<Event, RuleError> {
+        match action {
+            Action::BuildRoad { player, path } => {
+                if path.0 >= self.board.paths.len() {
+                    return Err(RuleError::UnknownPath);
+                }
+                if self.state.roads.iter().any(|road| road.id == path) {
+                    return Err(RuleError::IllegalRoad);
+                }
+                if self.remaining_pieces(player).roads == 0 {
+                    return Err(RuleError::NoPiecesLeft);
+                }
+                if let Phase::Setup { step, settlement: Some(settlement) } = self.state.turn.phase {
+                    let Path(a, b) = self.board.paths[path.0];
+                    if a != settlement && b != settlement {
+                        return Err(RuleError::IllegalRoad);
+                    }
+                    self.state.roads.push(Road { id: path, player });
+                    self.advance_setup(step + 1);
+                } else if let Phase::RoadBuilding { roads } = self.state.turn.phase {
+                    if !self.possible_road_paths(player).contains(&self.board.paths[path.0]) {
+                        return Err(RuleError::IllegalRoad);
+                    }
+                    self.state.roads.push(Road { id: path, player });
+                    self.continue_road_building(roads - 1);
+                } else {
+                    if !self.possible_road_paths(player).contains(&self.board.paths[path.0]) {
+                        return Err(RuleError::IllegalRoad);
+                    }
+                    self.pay(player, ROAD_COST)?;
+                    self.state.roads.push(Road { id: path, player });
+                }
+                self.update_longest_road();
+                Ok(Event::RoadBuilt { player, path })
+            }
+            Action::BuildSettlement { player, intersection } => {
+                if intersection.0 >= self.board.intersections.len() {
+                    return Err(RuleError::UnknownIntersection);
+                }
+                if self.remaining_pieces(player).settlements == 0 {
+                    return Err(RuleError::NoPiecesLeft);
+                }
+                if let Phase::Setup { step, .. } = self.state.turn.phase {
+                    if !self.setup_intersections().contains(&intersection) {
+                        return Err(RuleError::IllegalSettlement);
+                    }
+                    if step >= self.state.players.len() {
+                        self.grant_starting_resources(player, intersection);
+                    }
+                    self.state.turn.phase = Phase::Setup { step, settlement: Some(intersection) };
+                } else {
+                    if !self.legal_settlements(player).contains(&intersection) {
+                        return Err(RuleError::IllegalSettlement);
+                    }
+                    self.pay(player, SETTLEMENT_COST)?;
+                }
+                self.state.buildings.push(Building {
+                    intersection_id: intersection,
+                    kind: BuildingKind::Settlement,
+                    player,
+                });
+                self.update_longest_road();
+                Ok(Event::SettlementBuilt { player, intersection })
+            }
+            Action::BuildCity { player, intersection } => {
+                if intersection.0 >= self.board.intersections.len() {
+                    return Err(RuleError::UnknownIntersection);
+                }
+                if self.remaining_pieces(player).cities == 0 {
+                    return Err(RuleError::NoPiecesLeft);
+                }
+                if !self.legal_cities(player).contains(&intersection) {
+                    return Err(RuleError::IllegalCity);
+                }
+                self.pay(player, CITY_COST)?;
+                let building = self.state.buildings.iter_mut()
+                    .find(|building| building.intersection_id == intersection)
+                    .unwrap();
+                building.kind = BuildingKind::City;
+                Ok(Event::CityBuilt { player, intersection })
+            }
+            Action::RollDice { player } => {
+                let roll = self.state.rng.roll();
+                let produced = self.resolve_roll(roll);
+                Ok(Event::DiceRolled { player, roll, produced })
+            }
+            Action::Discard { player, cards } => {
+                let hand = self.state.resources[player];
+                if !cards.is_positive() || cards.total() != hand.total() / 2 || !(hand - cards).is_positive() {
+                    return Err(RuleError::InvalidDiscard);
+                }
+                self.state.resources[player] = hand - cards;
+                self.state.bank.resources = self.state.bank.resources + cards;
+                if let Phase::Discard { players } = &mut self.state.turn.phase {
+                    players.retain(|p| *p != player);
+                    if players.is_empty() {
+                        self.state.turn.phase = Phase::MoveRobber;
+                    }
+                }
+                Ok(Event::Discarded { player, cards })
+            }
+            Action::MoveRobber { player, robber, victim } => {
+                if robber.0 >= self.board.tiles.len() {
+                    return Err(RuleError::UnknownTile);
+                }
+                if robber == self.state.robber {
+                    return Err(RuleError::RobberNotMoved);
+                }
+                let targets = self.steal_targets(player, robber);
+                let stolen = match victim {
+                    Some(victim) if targets.contains(&victim) => self.steal(player, victim),
+                    None if targets.is_empty() => None,
+                    _ => return Err(RuleError::IllegalVictim),
+                };
+                self.state.robber = robber;
+                self.state.turn.phase = self.state.turn.resume();
+                Ok(Event::RobberMoved { player, robber, victim, stolen })
+            }
+            Action::BuyDevelopmentCard { player } => {
+                if self.state.bank.development.is_empty() {
+                    return Err(RuleError::DevelopmentDeckEmpty);
+                }
+                self.pay(player, DEVELOPMENT_COST)?;
+                let card = self.state.bank.development.pop().unwrap();
+                self.state.development_cards.push(HeldCard { player, card, bought_turn: self.state.turn.number });
+                Ok(Event::DevelopmentCardBought { player, card })
+            }
+            Action::PlayKnight { player } => {
+                self.play_development_card(player, DevelopmentCard::Knight)?;
+                self.state.played_knights.push(player);
+                self.update_largest_army();
+                self.state.turn.phase = Phase::MoveRobber;
+                Ok(Event::DevelopmentCardPlayed { player, card: DevelopmentCard::Knight })
+            }
+            Action::PlayRoadBuilding { player } => {
+                self.play_development_card(player, DevelopmentCard::RoadBuilding)?;
+                self.continue_road_building(2);
+                Ok(Event::DevelopmentCardPlayed { player, card: DevelopmentCard::RoadBuilding })
+            }
+            Action::PlayYearOfPlenty { player, first, second } => {
+                if !RESOURCES.contains(&first) || !RESOURCES.contains(&second) {
+                    return Err(RuleError::UnknownResource);
+                }
+                let cards = ResourceCount::single(first, 1) + ResourceCount::single(second, 1);
+                if !(self.state.bank.resources - cards).is_positive() {
+                    return Err(RuleError::NotEnoughInBank);
+                }
+                self.play_development_card(player, DevelopmentCard::YearOfPlenty)?;
+                self.state.bank.resources = self.state.bank.resources - cards;
+                self.state.resources[player] = self.state.resources[player] + cards;
+                Ok(Event::DevelopmentCardPlayed { player, card: DevelopmentCard::YearOfPlenty })
+            }
+            Action::PlayMonopoly { player, resource } => {
+                if !RESOURCES.contains(&resource) {
+                    return Err(RuleError::UnknownResource);
+                }
+                self.play_development_card(player, DevelopmentCard::Monopoly)?;
+                for other in self.state.players.clone().iter().filter(|other| **other != player) {
+                    let cards = ResourceCount::single(resource, self.state.resources[*other][resource]);
+                    self.state.resources[*other] = self.state.resources[*other] - cards;
+                    self.state.resources[player] = self.state.resources[player] + cards;
+                }
+                Ok(Event::DevelopmentCardPlayed { player, card: DevelopmentCard::Monopoly })
+            }
+            Action::TradeWithBank { player, give, get } => {
+                if !RESOURCES.contains(&give) || !RESOURCES.contains(&get) {
+                    return Err(RuleError::UnknownResource);
+                }
+                if give == get {
+                    return Err(RuleError::IllegalTrade);
+                }
+                let received = ResourceCount::single(get, 1);
+                if !(self.state.bank.resources - received).is_positive() {
+                    return Err(RuleError::NotEnoughInBank);
+                }
+                let gave = ResourceCount::single(give, self.trade_ratio(player, give));
+                self.pay(player, gave)?;
+                self.state.bank.resources = self.state.bank.resources - received;
+                self.state.resources[player] = self.state.resources[player] + received;
+                Ok(Event::TradedWithBank { player, gave, received })
+            }
+            Action::OfferTrade { offer } => {
+                if offer.to.is_some_and(|to| !self.state.players.contains(&to)) {
+                    return Err(RuleError::IllegalTrade);
+                }
+                offer.validate(&self.state.resources)?;
+                let waiting = self.state.players.iter()
+                    .copied()
+                    .filter(|player| *player != offer.from && offer.to.map_or(true, |to| to == *player))
+                    .collect();
+                self.state.turn.phase = Phase::Trade { offer, waiting };
+                Ok(Event::TradeOffered { offer })
+            }
+            Action::AcceptTrade { player } => {
+                let offer = match &self.state.turn.phase {
+                    Phase::Trade { offer, .. } => *offer,
+                    _ => return Err(RuleError::WrongPhase),
+                };
+                if !offer.can_accept(player, &self.state.resources) {
+                    return Err(RuleError::NotEnoughResources);
+                }
+                self.state.resources[offer.from] = self.state.resources[offer.from] - offer.give + offer.get;
+                self.state.resources[player] = self.state.resources[player] - offer.get + offer.give;
+                self.state.turn.phase = Phase::Main;
+                Ok(Event::Traded { offer, with: player })
+            }
+            Action::RejectTrade { player } => {
+                if let Phase::Trade { offer, waiting } = &mut self.state.turn.phase {
+                    waiting.retain(|p| *p != player);
+                    if waiting.is_empty() || offer.from == player {
+                        self.state.turn.phase = Phase::Main;
+                    }
+                }
+                Ok(Event::TradeRejected { player })
+            }
+            Action::CounterTrade { offer } => {
+                if offer.to != Some(self.state.turn.player) {
+                    return Err(RuleError::IllegalTrade);
+                }
+                offer.validate(&self.state.resources)?;
+                let turn_player = self.state.turn.player;
+                self.state.turn.phase = Phase::Trade { offer, waiting: vec![turn_player] };
+                Ok(Event::TradeOffered { offer })
+            }
+            Action::EndTurn { player } => {
+                let builders = match &self.state.turn.phase {
+                    Phase::SpecialBuilding { players } => players[1..].to_vec(),
+                    _ if self.board.layout.special_building() => self.special_builders(player),
+                    _ => vec![],
+                };
+                if !builders.is_empty() {
+                    self.state.turn.phase = Phase::SpecialBuilding { players: builders };
+                    return Ok(Event::TurnEnded { player });
+                }
+                let current = self.state.turn.player;
+                self.state.turn = Turn {
+                    player: self.state.next_player(current),
+                    phase: Phase::Roll,
+                    number: self.state.turn.number + 1,
+                    rolled: false,
+                    development_played: false,
+                };
+                Ok(Event::TurnEnded { player })
+            }
+        }
+    }
+
+    /// Checks that `action` is allowed in the current phase and performed by a player who may act.
+    fn check_phase(&self, action: &Action) -> Result<(), RuleError> {
+        let turn = &self.state.turn;
+        match (&turn.phase, action) {
+            (Phase::GameOver, _) => Err(RuleError::GameOver),
+            (Phase::Discard { players }, Action::Discard { player, .. }) => {
+                if players.contains(player) { Ok(()) } else { Err(RuleError::NotYourTurn) }
+            }
+            (Phase::Discard { .. }, _) => Err(RuleError::WrongPhase),
+            (Phase::Trade { waiting, .. }, Action::AcceptTrade { player })
+            | (Phase::Trade { waiting, .. }, Action::CounterTrade { offer: TradeOffer { from: player, .. } }) => {
+                if waiting.contains(player) { Ok(()) } else { Err(RuleError::NotYourTurn) }
+            }
+            (Phase::Trade { offer, waiting }, Action::RejectTrade { player }) => {
+                if waiting.contains(player) || offer.from == *player { Ok(()) } else { Err(RuleError::NotYourTurn) }
+            }
+            (Phase::Trade { .. }, _) => Err(RuleError::WrongPhase),
+            (Phase::SpecialBuilding { players }, Action::BuildRoad { player, .. })
+            | (Phase::SpecialBuilding { players }, Action::BuildSettlement { player, .. })
+            | (Phase::SpecialBuilding { players }, Action::BuildCity { player, .. })
+            | (Phase::SpecialBuilding { players }, Action::BuyDevelopmentCard { player })
+            | (Phase::SpecialBuilding { players }, Action::EndTurn { player }) => {
+                if players.first() == Some(player) { Ok(()) } else { Err(RuleError::NotYourTurn) }
+            }
+            (Phase::SpecialBuilding { .. }, _) => Err(RuleError::WrongPhase),
+            _ if action.player() != turn.player => Err(RuleError::NotYourTurn),
+            (Phase::Setup { settlement: None, .. }, Action::BuildSettlement { .. })
+            | (Phase::Setup { settlement: Some(_), .. }, Action::BuildRoad { .. })
+            | (Phase::Roll, Action::RollDice { .. })
+            | (Phase::MoveRobber, Action::MoveRobber { .. })
+            | (Phase::RoadBuilding { .. }, Action::BuildRoad { .. })
+            | (Phase::Roll | Phase::Main, Action::PlayKnight { .. })
+            | (Phase::Roll | Phase::Main, Action::PlayRoadBuilding { .. })
+            | (Phase::Roll | Phase::Main, Action::PlayYearOfPlenty { .. })
+            | (Phase::Roll | Phase::Main, Action::PlayMonopoly { .. })
+            | (Phase::Main, Action::BuyDevelopmentCard { .. })
+            | (Phase::Main, Action::TradeWithBank { .. })
+            | (Phase::Main, Action::OfferTrade { .. })
+            | (Phase::Main, Action::BuildRoad { .. })
+            | (Phase::Main, Action::BuildSettlement { .. })
+            | (Phase::Main, Action::BuildCity { .. })
+            | (Phase::Main, Action::EndTurn { .. }) => Ok(()),
+            _ => Err(RuleError::WrongPhase),
+        }
+    }
+
+    /// Pays out the production of `roll` and moves the turn to the next phase.
+    ///
+    /// On a 7 nothing is produced; every player holding more than 7 cards has to discard,
+    /// and then the robber is moved.
+    ///
+    /// # Returns
+    /// The cards each player received.
+    pub(crate) fn resolve_roll(&mut self, roll: u8) -> PlayerResourceCount {
+        self.state.turn.rolled = true;
+        let produced = if roll == 7 {
+            PlayerResourceCount::default()
+        } else {
+            self.produce(roll)
+        };
+        self.state.turn.phase = if roll == 7 {
+            let players: Vec<Player> = self.state.players.iter()
+                .copied()
+                .filter(|player| self.state.resources[*player].total() > 7)
+                .collect();
+            if players.is_empty() {
+                Phase::MoveRobber
+            } else {
+                Phase::Discard { players }
+            }
+        } else {
+            Phase::Main
+        };
+        produced
+    }
+
+    /// Returns the players who may build after the turn of `player`, in turn order.
+    fn special_builders(&self, player: Player) -> Vec<Player> {
+        let mut builders = vec![];
+        let mut next = self.state.next_player(player);
+        while next != player {
+            builders.push(next);
+            next = self.state.next_player(next);
+        }
+        builders
+    }
+
+    /// Moves the setup phase on to `step`, or to the first regular turn once every player has placed twice.
+    fn advance_setup(&mut self, step: usize) {
+        self.state.turn = if step < setup_steps(&self.state.players) {
+            Turn {
+                player: setup_player(&self.state.players, step),
+                phase: Phase::Setup { step, settlement: None },
+                number: 0,
+                rolled: false,
+                development_played: false,
+            }
+        } else {
+            Turn::new(self.state.players[0])
+        };
+    }
+
+    /// Moves one random resource card from the hand of `victim` to the hand of `player`.
+    ///
+    /// Returns the kind of the stolen card, or `None` if the victim holds no cards.
+    fn steal(&mut self, player: Player, victim: Player) -> Option<TileKind> {
+        let hand = self.state.resources[victim];
+        if hand.total() == 0 {
+            return None;
+        }
+        let mut index = self.state.rng.below(hand.total() as usize) as i32;
+        let mut kind = TileKind::Nothing;
+        for resource in RESOURCES.iter() {
+            if index < hand[*resource] as i32 {
+                kind = *resource;
+                break;
+            }
+            index -= hand[*resource] as i32;
+        }
+        let card = ResourceCount::single(kind, 1);
+        self.state.resources[victim] = hand - card;
+        self.state.resources[player] = self.state.resources[player] + card;
+        Some(kind)
+    }
+
+    /// Removes a playable development card of kind `card` from the hand of `player`.
+    ///
+    /// Returns `RuleError::CardNotPlayable` if the player holds no such card bought before
+    /// this turn, or already played a development card this turn.
+    fn play_development_card(&mut self, player: Player, card: DevelopmentCard) -> Result<(), RuleError> {
+        if self.take_development_card(player, card) {
+            Ok(())
+        } else {
+            Err(RuleError::CardNotPlayable)
+        }
+    }
+
+    /// Lets the current player build `roads` more free roads, or resumes the turn when no
+    /// free road is left or no legal path remains.
+    fn continue_road_building(&mut self, roads: u8) {
+        let player = self.state.turn.player;
+        self.state.turn.phase = if roads > 0
+            && self.remaining_pieces(player).roads > 0
+            && !self.possible_road_paths(player).is_empty() {
+            Phase::RoadBuilding { roads }
+        } else {
+            self.state.turn.resume()
+        };
+    }
+
+    /// Deducts `cost` from the resources of `player` and returns the cards to the bank.
+    ///
+    /// Returns `RuleError::NotEnoughResources` without changing anything if the player cannot pay.
+    fn pay(&mut self, player: Player, cost: ResourceCount) -> Result<(), RuleError> {
+        let remaining = self.state.resources[player] - cost;
+        if !remaining.is_positive() {
+            return Err(RuleError::NotEnoughResources);
+        }
+        self.state.resources[player] = remaining;
+        self.state.bank.resources = self.state.bank.resources + cost;
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::dice::Rng;
+    use crate::game::test_support::{crowded_game, main_phase};
+
+    fn get_game() -> Game {
+        let mut game = crowded_game(Some("   G  W  B  L  O\nW  1  1  1  1  1\nR  3  0  2  2  2\nB  0  0  0  0  0"));
+        // Red has not upgraded the settlement on 40 yet.
+        game.state.buildings.iter_mut().find(|building| building.intersection_id == IntersectionId(40)).unwrap().kind = BuildingKind::Settlement;
+        game
+    }
+
+    #[test]
+    fn test_apply_build_settlement() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::White);
+        let action = Action::BuildSettlement { player: Player::White, intersection: IntersectionId(4) };
+        assert_eq!(game.apply(action), Ok(Event::SettlementBuilt { player: Player::White, intersection: IntersectionId(4) }));
+        assert_eq!(game.state.resources.white, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 0, ore: 1 });
+        assert_eq!(game.apply(action), Err(RuleError::IllegalSettlement));
+    }
+
+    #[test]
+    fn test_apply_rejects_illegal_moves() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::White);
+        let too_close = Action::BuildSettlement { player: Player::White, intersection: IntersectionId(36) };
+        assert_eq!(game.apply(too_close), Err(RuleError::IllegalSettlement));
+
+        let occupied = Action::BuildRoad { player: Player::White, path: PathId(37) };
+        assert_eq!(game.apply(occupied), Err(RuleError::IllegalRoad));
+
+        let poor = Action::BuildRoad { player: Player::Blue, path: PathId(14) };
+        assert_eq!(game.apply(poor), Err(RuleError::NotYourTurn));
+        main_phase(&mut game, Player::Blue);
+        assert_eq!(game.apply(poor), Err(RuleError::NotEnoughResources));
+
+        game.state.turn.phase = Phase::MoveRobber;
+        let robber = Action::MoveRobber { player: Player::Blue, robber: RobberId(7), victim: None };
+        assert_eq!(game.apply(robber), Err(RuleError::RobberNotMoved));
+        assert_eq!(game.state.resources.blue, ResourceCount::default());
+    }
+
+    #[test]
+    fn test_apply_build_city() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        let action = Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) };
+        assert_eq!(game.apply(action), Ok(Event::CityBuilt { player: Player::Red, intersection: IntersectionId(10) }));
+        assert_eq!(game.state.resources.red, ResourceCount { grain: 0, wool: 0, brick: 2, lumber: 2, ore: 0 });
+        assert_eq!(game.state.bank.resources, ResourceCount { grain: 18, wool: 18, brick: 16, lumber: 16, ore: 18 });
+        assert_eq!(game.apply(action), Err(RuleError::IllegalCity));
+    }
+
+    #[test]
+    fn test_apply_winning_move_ends_game() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.victory_target = 5;
+        assert!(game.apply(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::GameOver);
+        assert_eq!(game.apply(Action::EndTurn { player: Player::Red }), Err(RuleError::GameOver));
+    }
+
+    #[test]
+    fn test_turn_phases() {
+        let mut game = get_game();
+        assert_eq!(game.state.turn.phase, Phase::Roll);
+        let build = Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) };
+        assert_eq!(game.apply(build), Err(RuleError::WrongPhase));
+        assert_eq!(game.apply(Action::RollDice { player: Player::Blue }), Err(RuleError::NotYourTurn));
+
+        game.resolve_roll(8);
+        assert_eq!(game.state.turn.phase, Phase::Main);
+        assert_eq!(game.apply(Action::EndTurn { player: Player::Red }), Ok(Event::TurnEnded { player: Player::Red }));
+        assert_eq!(game.state.turn, Turn { player: Player::Blue, phase: Phase::Roll, number: 1, rolled: false, development_played: false });
+        assert_eq!(game.possible_actions(Player::Blue), vec![Action::RollDice { player: Player::Blue }]);
+        assert!(game.possible_actions(Player::Red).is_empty());
+    }
+
+    #[test]
+    fn test_special_building_phase() {
+        let tiles = (0..EXTENSION_TILES).map(|_| Tile { dice: 5, kind: TileKind::Grain }).collect();
+        let mut game = Game::new(Board::new(tiles), 1);
+        assert_eq!(game.state.players, Player::ALL.to_vec());
+        game.state.buildings.push(Building { intersection_id: IntersectionId(0), kind: BuildingKind::Settlement, player: Player::Blue });
+        game.state.resources.blue = ROAD_COST;
+        main_phase(&mut game, Player::Red);
+
+        assert_eq!(game.apply(Action::EndTurn { player: Player::Red }), Ok(Event::TurnEnded { player: Player::Red }));
+        let builders = vec![Player::Blue, Player::White, Player::Orange, Player::Green, Player::Brown];
+        assert_eq!(game.state.turn.phase, Phase::SpecialBuilding { players: builders });
+        assert_eq!(game.apply(Action::EndTurn { player: Player::White }), Err(RuleError::NotYourTurn));
+        assert_eq!(game.apply(Action::RollDice { player: Player::Blue }), Err(RuleError::WrongPhase));
+
+        let road = Action::BuildRoad { player: Player::Blue, path: PathId(0) };
+        let down = Action::BuildRoad { player: Player::Blue, path: PathId(6) };
+        assert_eq!(game.possible_actions(Player::Blue), vec![road, down, Action::EndTurn { player: Player::Blue }]);
+        assert!(game.apply(road).is_ok());
+        for player in [Player::Blue, Player::White, Player::Orange, Player::Green, Player::Brown] {
+            assert_eq!(game.state.turn.player, Player::Red);
+            assert!(game.apply(Action::EndTurn { player }).is_ok());
+        }
+        assert_eq!(game.state.turn, Turn { player: Player::Blue, phase: Phase::Roll, number: 2, rolled: false, development_played: false });
+    }
+
+    #[test]
+    fn test_new_game_shuffles_the_deck_once() {
+        let tiles = (0..EXTENSION_TILES).map(|_| Tile { dice: 5, kind: TileKind::Grain }).collect();
+        let game = Game::new(Board::new(tiles), 3);
+        let mut deck = Layout::Extension.development_deck();
+        Rng::new(3).shuffle(&mut deck);
+        assert_eq!(game.state.bank.development, deck);
+    }
+
+    #[test]
+    fn test_turn_order_follows_players() {
+        let mut game = get_game();
+        assert_eq!(game.state.next_player(Player::White), Player::Red);
+        game.state.players = Player::FOUR.to_vec();
+        assert_eq!(game.state.next_player(Player::White), Player::Orange);
+        assert_eq!(game.state.next_player(Player::Orange), Player::Red);
+
+        game.state.turn = Turn { player: Player::White, phase: Phase::Main, number: 3, rolled: true, development_played: false };
+        assert!(game.apply(Action::EndTurn { player: Player::White }).is_ok());
+        assert_eq!(game.state.turn.player, Player::Orange);
+    }
+
+    #[test]
+    fn test_roll_seven_discards_then_robber() {
+        let mut game = get_game();
+        game.state.resources.red = ResourceCount { grain: 3, wool: 0, brick: 2, lumber: 2, ore: 0 };
+        game.state.resources.blue = ResourceCount { grain: 3, wool: 0, brick: 2, lumber: 3, ore: 1 };
+        game.resolve_roll(7);
+        assert_eq!(game.state.turn.phase, Phase::Discard { players: vec![Player::Blue] });
+        assert_eq!(game.apply(Action::EndTurn { player: Player::Red }), Err(RuleError::WrongPhase));
+
+        let too_few = ResourceCount { grain: 3, wool: 0, brick: 0, lumber: 0, ore: 0 };
+        assert_eq!(game.apply(Action::Discard { player: Player::Blue, cards: too_few }), Err(RuleError::InvalidDiscard));
+        let cards = ResourceCount { grain: 1, wool: 0, brick: 1, lumber: 2, ore: 0 };
+        let options = game.possible_actions(Player::Blue);
+        assert_eq!(options.len(), 19);
+        assert!(options.contains(&Action::Discard { player: Player::Blue, cards }));
+        assert!(game.apply(Action::Discard { player: Player::Blue, cards }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::MoveRobber);
+
+        assert!(game.apply(Action::MoveRobber { player: Player::Red, robber: RobberId(9), victim: None }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::Main);
+    }
+
+    #[test]
+    fn test_setup_phase() {
+        let mut game = get_game();
+        game.state.buildings.clear();
+        game.state.roads.clear();
+        game.state.turn = Turn::setup(&game.state.players);
+
+        let road = Action::BuildRoad { player: Player::Red, path: PathId(0) };
+        assert_eq!(game.apply(road), Err(RuleError::WrongPhase));
+        assert!(game.apply(Action::BuildSettlement { player: Player::Red, intersection: IntersectionId(0) }).is_ok());
+        assert_eq!(game.apply(Action::BuildRoad { player: Player::Red, path: PathId(2) }), Err(RuleError::IllegalRoad));
+        assert!(game.apply(road).is_ok());
+        assert_eq!(game.state.resources.red, ResourceCount { grain: 3, wool: 0, brick: 2, lumber: 2, ore: 2 });
+        assert_eq!(game.state.turn.player, Player::Blue);
+        let too_close = Action::BuildSettlement { player: Player::Blue, intersection: IntersectionId(1) };
+        assert_eq!(game.apply(too_close), Err(RuleError::IllegalSettlement));
+    }
+
+    #[test]
+    fn test_setup_starting_resources() {
+        let mut game = get_game();
+        game.state.buildings.clear();
+        game.state.roads.clear();
+        game.state.resources = PlayerResourceCount::default();
+        game.state.turn = Turn::setup(&game.state.players);
+
+        for step in 0..setup_steps(&game.state.players) {
+            let player = setup_player(&game.state.players, step);
+            let settlement = game.possible_actions(player)[0];
+            let intersection = match settlement {
+                Action::BuildSettlement { intersection, .. } => intersection,
+                action => panic!("unexpected action {:?}", action),
+            };
+            let before = game.state.resources[player];
+            assert!(game.apply(settlement).is_ok());
+            let producing = game.board.tiles_of(intersection).iter()
+                .filter(|tile| game.board.tiles[tile.0].kind != TileKind::Nothing)
+                .count() as i32;
+            let expected = if step < game.state.players.len() { 0 } else { producing };
+            assert_eq!(game.state.resources[player].total() - before.total(), expected);
+            let road = game.possible_actions(player)[0];
+            assert!(game.apply(road).is_ok());
+        }
+        assert_eq!(game.state.turn, Turn::new(Player::Red));
+        assert_eq!(game.state.buildings.len(), 6);
+        assert!(game.state.resources.sum().total() > 0);
+    }
+
+    fn give_card(game: &mut Game, player: Player, card: DevelopmentCard) {
+        game.state.development_cards.push(HeldCard { player, card, bought_turn: 0 });
+    }
+
+    #[test]
+    fn test_buy_development_card() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        let buy = Action::BuyDevelopmentCard { player: Player::Red };
+        assert_eq!(game.apply(buy), Err(RuleError::NotEnoughResources));
+
+        game.state.resources.red.wool = 1;
+        let card = *game.state.bank.development.last().unwrap();
+        assert_eq!(game.apply(buy), Ok(Event::DevelopmentCardBought { player: Player::Red, card }));
+        assert_eq!(game.state.bank.development.len(), 24);
+        assert_eq!(game.development_cards(Player::Red), vec![card]);
+        assert_eq!(game.state.resources.red, ResourceCount { grain: 2, wool: 0, brick: 2, lumber: 2, ore: 1 });
+        assert!(game.playable_development_cards(Player::Red).is_empty());
+
+        game.state.bank.development.clear();
+        assert_eq!(game.apply(buy), Err(RuleError::DevelopmentDeckEmpty));
+    }
+
+    #[test]
+    fn test_play_knight_before_roll() {
+        let mut game = get_game();
+        game.state.turn.number = 1;
+        give_card(&mut game, Player::Red, DevelopmentCard::Knight);
+        give_card(&mut game, Player::Red, DevelopmentCard::Knight);
+        let knight = Action::PlayKnight { player: Player::Red };
+        assert_eq!(game.apply(knight), Ok(Event::DevelopmentCardPlayed { player: Player::Red, card: DevelopmentCard::Knight }));
+        assert_eq!(game.state.turn.phase, Phase::MoveRobber);
+        assert_eq!(game.knights_played(Player::Red), 1);
+
+        assert!(game.apply(Action::MoveRobber { player: Player::Red, robber: RobberId(9), victim: None }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::Roll);
+        assert_eq!(game.apply(knight), Err(RuleError::CardNotPlayable));
+        assert_eq!(game.development_cards(Player::Red), vec![DevelopmentCard::Knight]);
+    }
+
+    #[test]
+    fn test_play_progress_cards() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        give_card(&mut game, Player::Red, DevelopmentCard::Monopoly);
+        give_card(&mut game, Player::Red, DevelopmentCard::YearOfPlenty);
+        game.state.resources.blue.brick = 3;
+        assert!(game.apply(Action::PlayMonopoly { player: Player::Red, resource: TileKind::Brick }).is_ok());
+        assert_eq!(game.state.resources.red.brick, 6);
+        assert_eq!(game.state.resources.white.brick, 0);
+        assert_eq!(game.state.resources.blue.brick, 0);
+
+        let plenty = Action::PlayYearOfPlenty { player: Player::Red, first: TileKind::Ore, second: TileKind::Ore };
+        assert_eq!(game.apply(plenty), Err(RuleError::CardNotPlayable));
+        main_phase(&mut game, Player::Red);
+        assert!(game.apply(plenty).is_ok());
+        assert_eq!(game.state.resources.red.ore, 4);
+        assert_eq!(game.state.bank.resources.ore, 14);
+    }
+
+    #[test]
+    fn test_play_road_building() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::White);
+        game.state.resources.white = ResourceCount::default();
+        give_card(&mut game, Player::White, DevelopmentCard::RoadBuilding);
+        assert!(game.apply(Action::PlayRoadBuilding { player: Player::White }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::RoadBuilding { roads: 2 });
+        assert_eq!(game.apply(Action::EndTurn { player: Player::White }), Err(RuleError::WrongPhase));
+
+        let path = match game.possible_actions(Player::White)[0] {
+            Action::BuildRoad { path, .. } => path,
+            action => panic!("unexpected action {:?}", action),
+        };
+        assert!(game.apply(Action::BuildRoad { player: Player::White, path }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::RoadBuilding { roads: 1 });
+        let path = match game.possible_actions(Player::White)[0] {
+            Action::BuildRoad { path, .. } => path,
+            action => panic!("unexpected action {:?}", action),
+        };
+        assert!(game.apply(Action::BuildRoad { player: Player::White, path }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::Main);
+        assert_eq!(game.state.resources.white, ResourceCount::default());
+    }
+
+    #[test]
+    fn test_move_robber_steals() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.turn.phase = Phase::MoveRobber;
+        assert_eq!(game.steal_targets(Player::Red, RobberId(3)), vec![Player::White]);
+        assert_eq!(game.robber_placements().len(), 18);
+
+        let nobody = Action::MoveRobber { player: Player::Red, robber: RobberId(3), victim: None };
+        assert_eq!(game.apply(nobody), Err(RuleError::IllegalVictim));
+        let no_cards = Action::MoveRobber { player: Player::Red, robber: RobberId(3), victim: Some(Player::Blue) };
+        assert_eq!(game.apply(no_cards), Err(RuleError::IllegalVictim));
+
+        let rob = Action::MoveRobber { player: Player::Red, robber: RobberId(3), victim: Some(Player::White) };
+        let stolen = match game.apply(rob) {
+            Ok(Event::RobberMoved { stolen: Some(stolen), .. }) => stolen,
+            result => panic!("unexpected result {:?}", result),
+        };
+        assert_eq!(game.state.resources.white.total(), 4);
+        assert_eq!(game.state.resources.white[stolen], 0);
+        assert_eq!(game.state.resources.red.total(), 10);
+        assert_eq!(game.state.robber.0, 3);
+        assert_eq!(game.state.turn.phase, Phase::Main);
+    }
+}
@@ -1,6 +1,11 @@
 use std::cmp::PartialEq;
 use std::convert::TryFrom;
-use crate::game::resources::PlayerResourceCount;
+use crate::game::development::{development_deck, DevelopmentCard, HeldCard};
+use crate::game::dice::Rng;
+use crate::game::resources::{Bank, PlayerResourceCount, BANK_RESOURCES, EXTENSION_BANK_RESOURCES};
+use crate::game::scoring::VICTORY_TARGET;
+use crate::game::topology::{extension, hexagon, Adjacency, HexCoord, Topology};
+use crate::game::turn::Turn;
 
 /// An enumeration representing the players in the Settlers of Catan game.
 ///
@@ -8,6 +13,11 @@ use crate::game::resources::PlayerResourceCount;
 /// - `red`: The red player.
 /// - `blue`: The blue player.
 /// - `white`: The white player.
+/// - `orange`: The orange player.
+/// - `green`: The green player of the 5-6 player extension.
+/// - `brown`: The brown player of the 5-6 player extension.
+///
+/// A game is played by three to six of them, listed in `State.players`.
 ///
 /// This enum is used to specify the owner of buildings, roads, or other player-specific attributes.
 ///
@@ -20,11 +30,25 @@ use crate::game::resources::PlayerResourceCount;
 ///     Player::white => println!("white player's turn"),
 /// }
 /// ```
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Player {
     Red,
     Blue,
     White,
+    Orange,
+    Green,
+    Brown,
+}
+
+impl Player {
+    /// All player colors in turn order.
+    pub const ALL: [Player; 6] = [Player::Red, Player::Blue, Player::White, Player::Orange, Player::Green, Player::Brown];
+
+    /// The players of a three-player game, in turn order.
+    pub const THREE: [Player; 3] = [Player::Red, Player::Blue, Player::White];
+
+    /// The players of a four-player game, in turn order.
+    pub const FOUR: [Player; 4] = [Player::Red, Player::Blue, Player::White, Player::Orange];
 }
 
 
@@ -42,6 +66,9 @@ pub struct Path(pub IntersectionId, pub IntersectionId);
 /// - `'R'` -> `Player::red`
 /// - `'B'` -> `Player::blue`
 /// - `'W'` -> `Player::white`
+/// - `'O'` -> `Player::orange`
+/// - `'G'` -> `Player::green`
+/// - `'N'` -> `Player::brown`
 ///
 /// Returns an error string if the character is invalid.
 ///
@@ -63,6 +90,9 @@ impl TryFrom<char> for Player {
             'R' => Ok(Player::Red),
             'B' => Ok(Player::Blue),
             'W' => Ok(Player::White),
+            'O' => Ok(Player::Orange),
+            'G' => Ok(Player::Green),
+            'N' => Ok(Player::Brown),
             _ => Err("Invalid character for Player"),
         }
     }
@@ -90,7 +120,7 @@ impl TryFrom<char> for Player {
 ///     _ => println!("This tile produces another resource."),
 /// }
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum  TileKind {
     Grain,
     Wool,
@@ -103,24 +133,19 @@ pub enum  TileKind {
 
 
 /// A unique identifier for a tile in the Settlers of Catan game.
-#[derive(Debug)]
-struct TileId(usize);
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct TileId(pub usize);
 
 
 /// A unique identifier for the position of the robber on the game board.
-#[derive(Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct RobberId(pub usize);
 
 
 /// Represents a tile on the game board.
 ///
 /// Each tile has a dice value and a resource type (`TileKind`).
-
-
-/// Represents a tile on the game board.
-///
-/// Each tile has a dice value and a resource type (`TileKind`).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Tile {
     pub dice: u8,
     pub kind: TileKind
@@ -130,7 +155,7 @@ pub struct Tile {
 ///
 /// - `Settlement`: A basic building that provides fewer points/resources.
 /// - `City`: An upgraded building that provides more points/resources.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum BuildingKind {
     Settlement,
     City,
@@ -148,7 +173,7 @@ pub enum BuildingKind {
 /// assert_eq!(building.to_char(), 'C');
 /// ```
 impl BuildingKind {
-    fn to_char(&self) -> char {
+    pub(crate) fn to_char(self) -> char {
         match self {
             BuildingKind::Settlement => 'S',
             BuildingKind::City => 'C',
@@ -168,7 +193,7 @@ pub struct Building {
 
 /// Represents a road on the board, including its location (`PathId`)
 /// and the player who owns it.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Road {
     pub id: PathId,
     pub player: Player
@@ -176,216 +201,356 @@ pub struct Road {
 
 
 /// A unique identifier for a path (road) in the Settlers of Catan game.
-#[derive(Debug, Eq, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct PathId(pub usize);
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Intersection {
     pub paths: Vec<PathId>,
-    tiles: Vec<TileId>,
+    pub tiles: Vec<TileId>,
 }
 
-/// Represents an intersection on the game board.
+
+pub const PATHS: usize = 72;
+pub const INTERSECTIONS: usize = 54;
+pub const TILES: usize = 19;
+
+pub const EXTENSION_PATHS: usize = 109;
+pub const EXTENSION_INTERSECTIONS: usize = 80;
+pub const EXTENSION_TILES: usize = 30;
+
+/// An enumeration of the board layouts.
 ///
-/// Each intersection connects multiple paths (`PathId`) and may touch multiple tiles (`TileId`).
-impl Intersection {
-    /// Creates a new `Intersection` with the given paths and tiles.
-    fn new(paths: Vec<PathId>, tiles: Vec<TileId>) -> Intersection {
-        Self {
-            paths,
-            tiles,
+/// - `Standard`: The base game board with 19 tiles, for three or four players.
+/// - `Extension`: The 5-6 player extension board with 30 tiles. It comes with more harbors,
+///   a larger bank and development deck, and a special building phase after every turn.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Layout {
+    Standard,
+    Extension,
+}
+
+impl Layout {
+    /// Returns the layout with `tiles` tiles, if there is one.
+    pub fn for_tiles(tiles: usize) -> Option<Layout> {
+        match tiles {
+            TILES => Some(Layout::Standard),
+            EXTENSION_TILES => Some(Layout::Extension),
+            _ => None,
+        }
+    }
+
+    /// Returns the axial coordinates of the tiles, indexed by `TileId`.
+    pub fn coordinates(&self) -> Vec<HexCoord> {
+        match self {
+            Layout::Standard => hexagon(2),
+            Layout::Extension => extension(),
         }
     }
+
+    /// Returns the harbors the board comes with.
+    pub fn harbors(&self) -> Vec<Harbor> {
+        match self {
+            Layout::Standard => STANDARD_HARBORS.to_vec(),
+            Layout::Extension => EXTENSION_HARBORS.to_vec(),
+        }
+    }
+
+    /// Returns the number of cards of each resource in the bank at the start of the game.
+    pub fn bank_resources(&self) -> i8 {
+        match self {
+            Layout::Standard => BANK_RESOURCES,
+            Layout::Extension => EXTENSION_BANK_RESOURCES,
+        }
+    }
+
+    /// Returns the full development deck, unshuffled.
+    pub fn development_deck(&self) -> Vec<DevelopmentCard> {
+        development_deck(*self)
+    }
+
+    /// Returns the players of a new game on the board, in turn order.
+    pub fn players(&self) -> &'static [Player] {
+        match self {
+            Layout::Standard => &Player::FOUR,
+            Layout::Extension => &Player::ALL,
+        }
+    }
+
+    /// Tells whether the other players may build after every turn.
+    pub fn special_building(&self) -> bool {
+        *self == Layout::Extension
+    }
 }
 
-pub const PATHS: usize = 72;
-pub const INTERSECTIONS: usize = 54;
-pub const TILES: usize = 19;
+/// An enumeration of the kinds of harbors.
+///
+/// - `Generic`: Trades any resource at 3:1.
+/// - `Resource`: Trades the given resource at 2:1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HarborKind {
+    Generic,
+    Resource(TileKind),
+}
+
+/// Represents a harbor on the coast, reachable from the two intersections it is attached to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Harbor {
+    pub intersections: (IntersectionId, IntersectionId),
+    pub kind: HarborKind,
+}
+
+/// The nine harbors of the standard board: four 3:1 harbors and one 2:1 harbor per resource,
+/// spread around the coast clockwise from the top left.
+pub const STANDARD_HARBORS: [Harbor; 9] = [
+    Harbor { intersections: (IntersectionId(0), IntersectionId(1)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(3), IntersectionId(4)), kind: HarborKind::Resource(TileKind::Grain) },
+    Harbor { intersections: (IntersectionId(6), IntersectionId(14)), kind: HarborKind::Resource(TileKind::Ore) },
+    Harbor { intersections: (IntersectionId(26), IntersectionId(37)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(45), IntersectionId(46)), kind: HarborKind::Resource(TileKind::Wool) },
+    Harbor { intersections: (IntersectionId(51), IntersectionId(52)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(47), IntersectionId(48)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(28), IntersectionId(38)), kind: HarborKind::Resource(TileKind::Brick) },
+    Harbor { intersections: (IntersectionId(16), IntersectionId(17)), kind: HarborKind::Resource(TileKind::Lumber) },
+];
+
+/// The eleven harbors of the 5-6 player extension board: five 3:1 harbors, one 2:1 harbor per
+/// resource and a second 2:1 wool harbor, spread around the coast clockwise from the top left.
+pub const EXTENSION_HARBORS: [Harbor; 11] = [
+    Harbor { intersections: (IntersectionId(0), IntersectionId(1)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(3), IntersectionId(4)), kind: HarborKind::Resource(TileKind::Grain) },
+    Harbor { intersections: (IntersectionId(14), IntersectionId(15)), kind: HarborKind::Resource(TileKind::Ore) },
+    Harbor { intersections: (IntersectionId(26), IntersectionId(38)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(51), IntersectionId(52)), kind: HarborKind::Resource(TileKind::Wool) },
+    Harbor { intersections: (IntersectionId(62), IntersectionId(72)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(78), IntersectionId(79)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(75), IntersectionId(76)), kind: HarborKind::Resource(TileKind::Wool) },
+    Harbor { intersections: (IntersectionId(64), IntersectionId(65)), kind: HarborKind::Generic },
+    Harbor { intersections: (IntersectionId(40), IntersectionId(41)), kind: HarborKind::Resource(TileKind::Brick) },
+    Harbor { intersections: (IntersectionId(16), IntersectionId(28)), kind: HarborKind::Resource(TileKind::Lumber) },
+];
 
 /// Represents the game board in Settlers of Catan.
 ///
 /// The board consists of:
-/// - `paths`: An array of roads (`Path`) connecting intersections.
-/// - `intersections`: An array of intersections where buildings can be placed.
-/// - `tiles`: An array of resource tiles on the board.
+/// - `layout`: The shape of the board, standard or 5-6 player extension.
+/// - `paths`: The roads (`Path`) connecting intersections.
+/// - `intersections`: The intersections where buildings can be placed.
+/// - `tiles`: The resource tiles on the board.
+/// - `harbors`: The harbors on the coast.
+/// - `adjacency`: Lookup tables derived from `paths` and `intersections`, behind the
+///   neighbourhood queries (`tiles_of`, `corners_of`, `neighbours_of`, `paths_of`, `path_between`).
+#[derive(Clone)]
 pub struct Board {
-    pub paths: [Path; PATHS],
-    pub intersections: [Intersection; INTERSECTIONS],
-    pub tiles: [Tile; TILES]
+    pub layout: Layout,
+    pub paths: Vec<Path>,
+    pub intersections: Vec<Intersection>,
+    pub tiles: Vec<Tile>,
+    pub harbors: Vec<Harbor>,
+    pub(crate) adjacency: Adjacency,
 }
 
 impl Board {
     /// Creates a new `Board` with the given tiles.
     ///
-    /// The paths and intersections are pre-defined for the standard Catan board.
+    /// The layout is chosen by the number of tiles: 19 for the standard board, 30 for the 5-6
+    /// player extension. The paths and intersections are derived from the hex coordinates of
+    /// the tiles, and the harbors are the ones the layout comes with.
     ///
     /// # Arguments
-    /// - `tiles`: An array of 19 tiles representing the game board resources and their dice values.
+    /// - `tiles`: The tiles representing the game board resources and their dice values, in reading order.
+    ///
+    /// # Panics
+    /// Panics if no layout has that many tiles.
     ///
     /// Example usage:
     /// ```rust
-    /// let tiles = [
+    /// let tiles = vec![
     ///     Tile { dice: 10, kind: TileKind::grain },
     ///     Tile { dice: 2, kind: TileKind::wool },
     ///     // ...remaining tiles...
     /// ];
     /// let board = Board::new(tiles);
     /// ```
-    pub fn new(tiles: [Tile; 19]) -> Board {
-        let paths: [Path;72] = [
-            Path(IntersectionId(0), IntersectionId(1)), // 0
-            Path(IntersectionId(1), IntersectionId(2)), // 1
-            Path(IntersectionId(2), IntersectionId(3)), // 2
-            Path(IntersectionId(3), IntersectionId(4)), // 3
-            Path(IntersectionId(4), IntersectionId(5)), // 4
-            Path(IntersectionId(5), IntersectionId(6)), // 5
-            Path(IntersectionId(0), IntersectionId(8)), // 6
-            Path(IntersectionId(2), IntersectionId(10)),// 7
-            Path(IntersectionId(4), IntersectionId(12)),// 8
-            Path(IntersectionId(6), IntersectionId(14)),// 9
-            Path(IntersectionId(8), IntersectionId(7)), // 10
-            Path(IntersectionId(8), IntersectionId(9)), // 11
-            Path(IntersectionId(9), IntersectionId(10)), // 12
-            Path(IntersectionId(10), IntersectionId(11)), // 13
-            Path(IntersectionId(11), IntersectionId(12)), // 14
-            Path(IntersectionId(12), IntersectionId(13)), // 15
-            Path(IntersectionId(13), IntersectionId(14)), // 16
-            Path(IntersectionId(14), IntersectionId(15)), // 17
-            Path(IntersectionId(7), IntersectionId(17)), // 18
-            Path(IntersectionId(9), IntersectionId(19)), // 19
-            Path(IntersectionId(11), IntersectionId(21)), // 20
-            Path(IntersectionId(13), IntersectionId(23)), // 21
-            Path(IntersectionId(15), IntersectionId(25)), // 22
-            Path(IntersectionId(16), IntersectionId(17)), // 23
-            Path(IntersectionId(17), IntersectionId(18)), // 24
-            Path(IntersectionId(18), IntersectionId(19)), // 25
-            Path(IntersectionId(19), IntersectionId(20)), // 26
-            Path(IntersectionId(20), IntersectionId(21)), // 27
-            Path(IntersectionId(21), IntersectionId(22)), // 28
-            Path(IntersectionId(22), IntersectionId(23)), // 29
-            Path(IntersectionId(23), IntersectionId(24)), // 30
-            Path(IntersectionId(24), IntersectionId(25)), // 31
-            Path(IntersectionId(25), IntersectionId(26)), // 32
-            Path(IntersectionId(16), IntersectionId(27)), // 33
-            Path(IntersectionId(18), IntersectionId(29)), // 34
-            Path(IntersectionId(20), IntersectionId(31)), // 35
-            Path(IntersectionId(22), IntersectionId(33)), // 36
-            Path(IntersectionId(24), IntersectionId(35)), // 37
-            Path(IntersectionId(26), IntersectionId(37)), // 38
-            Path(IntersectionId(27), IntersectionId(28)), // 39
-            Path(IntersectionId(28), IntersectionId(29)), // 40
-            Path(IntersectionId(29), IntersectionId(30)), // 41
-            Path(IntersectionId(30), IntersectionId(31)), // 42
-            Path(IntersectionId(31), IntersectionId(32)), // 43
-            Path(IntersectionId(32), IntersectionId(33)), // 44
-            Path(IntersectionId(33), IntersectionId(34)), // 45
-            Path(IntersectionId(34), IntersectionId(35)), // 46
-            Path(IntersectionId(35), IntersectionId(36)), // 47
-            Path(IntersectionId(36), IntersectionId(37)), // 48
-            Path(IntersectionId(28), IntersectionId(38)), // 49
-            Path(IntersectionId(30), IntersectionId(40)), // 50
-            Path(IntersectionId(32), IntersectionId(42)), // 51
-            Path(IntersectionId(34), IntersectionId(44)), // 52
-            Path(IntersectionId(36), IntersectionId(46)), // 53
-            Path(IntersectionId(38), IntersectionId(39)), // 54
-            Path(IntersectionId(39), IntersectionId(40)), // 55
-            Path(IntersectionId(40), IntersectionId(41)), // 56
-            Path(IntersectionId(41), IntersectionId(42)), // 57
-            Path(IntersectionId(42), IntersectionId(43)), // 58
-            Path(IntersectionId(43), IntersectionId(44)), // 59
-            Path(IntersectionId(44), IntersectionId(45)), // 60
-            Path(IntersectionId(45), IntersectionId(45)), // 61
-            Path(IntersectionId(39), IntersectionId(47)), // 62
-            Path(IntersectionId(41), IntersectionId(49)), // 63
-            Path(IntersectionId(43), IntersectionId(51)), // 64
-            Path(IntersectionId(45), IntersectionId(53)), // 65
-            Path(IntersectionId(47), IntersectionId(48)), // 66
-            Path(IntersectionId(48), IntersectionId(49)), // 67
-            Path(IntersectionId(49), IntersectionId(50)), // 68
-            Path(IntersectionId(50), IntersectionId(51)), // 69
-            Path(IntersectionId(51), IntersectionId(52)), // 70
-            Path(IntersectionId(52), IntersectionId(53)), // 71
-        ];
-
-        let intersections: [Intersection; 54] = [
-            Intersection::new(vec![PathId(0), PathId(6)], vec![TileId(0)]), // 0
-            Intersection::new(vec![PathId(0), PathId(1)], vec![TileId(0)]), // 1
-            Intersection::new(vec![PathId(1), PathId(2), PathId(7)], vec![TileId(0), TileId(1)]), // 2
-            Intersection::new(vec![PathId(2), PathId(3)], vec![TileId(1)]), // 3
-            Intersection::new(vec![PathId(3), PathId(4), PathId(8)], vec![TileId(1), TileId(2)]), // 4
-            Intersection::new(vec![PathId(4), PathId(5)], vec![TileId(2)]), // 5
-            Intersection::new(vec![PathId(5), PathId(9)], vec![TileId(2)]), // 6
-            Intersection::new(vec![PathId(10), PathId(18)], vec![TileId(3)]),  // 7
-            Intersection::new(vec![PathId(10), PathId(6), PathId(11)], vec![TileId(3), TileId(0)]),  // 8
-            Intersection::new(vec![PathId(11), PathId(12), PathId(19)], vec![TileId(3), TileId(4)]), // 9
-            Intersection::new(vec![PathId(7), PathId(12), PathId(13)], vec![TileId(0), TileId(1), TileId(4)]), // 10
-            Intersection::new(vec![PathId(13), PathId(14), PathId(20)], vec![TileId(1), TileId(4), TileId(5)]), // 11
-            Intersection::new(vec![PathId(8), PathId(14), PathId(15)], vec![TileId(1), TileId(2), TileId(5)]), // 12
-            Intersection::new(vec![PathId(15), PathId(16), PathId(21)], vec![TileId(2), TileId(5), TileId(6)]), // 13
-            Intersection::new(vec![PathId(9), PathId(16), PathId(17)], vec![TileId(2), TileId(6)]), // 14
-            Intersection::new(vec![PathId(17), PathId(22)], vec![TileId(6)]), // 15
-            Intersection::new(vec![PathId(23), PathId(33)], vec![TileId(7)]), // 16
-            Intersection::new(vec![PathId(18), PathId(23), PathId(24)], vec![TileId(3), TileId(7)]), // 17
-            Intersection::new(vec![PathId(24), PathId(25), PathId(34)], vec![TileId(3), TileId(7), TileId(8)]), // 18
-            Intersection::new(vec![PathId(19), PathId(25), PathId(26)], vec![TileId(3), TileId(4), TileId(8)]), // 19
-            Intersection::new(vec![PathId(26), PathId(27), PathId(35)], vec![TileId(4), TileId(8), TileId(9)]), // 20
-            Intersection::new(vec![PathId(20), PathId(27), PathId(28)], vec![TileId(4), TileId(5), TileId(9)]), // 21
-            Intersection::new(vec![PathId(28), PathId(29), PathId(36)], vec![TileId(5), TileId(9), TileId(10)]), // 22
-            Intersection::new(vec![PathId(21), PathId(29), PathId(30)], vec![TileId(5), TileId(6), TileId(10)]), // 23
-            Intersection::new(vec![PathId(30), PathId(31), PathId(37)], vec![TileId(6), TileId(10), TileId(11)]), // 24
-            Intersection::new(vec![PathId(22), PathId(31), PathId(32)], vec![TileId(6), TileId(11)]), // 25
-            Intersection::new(vec![PathId(32), PathId(38)], vec![TileId(11)]), // 26
-            Intersection::new(vec![PathId(33), PathId(39)], vec![TileId(7)]), // 27
-            Intersection::new(vec![PathId(39), PathId(40), PathId(49)], vec![TileId(7), TileId(12)]), // 28
-            Intersection::new(vec![PathId(34), PathId(40), PathId(41)], vec![TileId(7), TileId(8), TileId(12)]), // 29
-            Intersection::new(vec![PathId(41), PathId(42), PathId(50)], vec![TileId(8), TileId(12), TileId(13)]), // 30
-            Intersection::new(vec![PathId(35), PathId(42), PathId(43)], vec![TileId(8), TileId(9), TileId(13)]), // 31
-            Intersection::new(vec![PathId(43), PathId(44), PathId(51)], vec![TileId(9), TileId(13), TileId(14)]), // 32
-            Intersection::new(vec![PathId(36), PathId(44), PathId(45)], vec![TileId(9), TileId(10), TileId(14)]), // 33
-            Intersection::new(vec![PathId(45), PathId(46), PathId(52)], vec![TileId(10), TileId(14), TileId(15)]), // 34
-            Intersection::new(vec![PathId(37), PathId(46), PathId(47)], vec![TileId(10), TileId(11), TileId(15)]), // 35
-            Intersection::new(vec![PathId(47), PathId(48), PathId(53)], vec![TileId(11), TileId(15)]), // 36
-            Intersection::new(vec![PathId(38), PathId(48)], vec![TileId(11)]), // 37
-            Intersection::new(vec![PathId(49), PathId(54)], vec![TileId(12)]), // 38
-            Intersection::new(vec![PathId(54), PathId(55), PathId(62)], vec![TileId(12), TileId(16)]), // 39
-            Intersection::new(vec![PathId(50), PathId(55), PathId(56)], vec![TileId(12), TileId(13), TileId(16)]), // 40
-            Intersection::new(vec![PathId(56), PathId(57), PathId(63)], vec![TileId(13), TileId(16), TileId(17)]), // 41
-            Intersection::new(vec![PathId(51), PathId(57), PathId(58)], vec![TileId(13), TileId(14), TileId(17)]), // 42
-            Intersection::new(vec![PathId(58), PathId(59), PathId(64)], vec![TileId(14), TileId(17), TileId(18)]), // 43
-            Intersection::new(vec![PathId(52), PathId(59), PathId(60)], vec![TileId(14), TileId(15), TileId(18)]), // 44
-            Intersection::new(vec![PathId(60), PathId(61), PathId(65)], vec![TileId(15), TileId(18)]), // 45
-            Intersection::new(vec![PathId(53), PathId(61)], vec![TileId(15)]), // 46
-            Intersection::new(vec![PathId(62), PathId(66)], vec![TileId(16)]), // 47
-            Intersection::new(vec![PathId(66), PathId(67)], vec![TileId(16)]), // 48
-            Intersection::new(vec![PathId(63), PathId(67), PathId(68)], vec![TileId(16), TileId(17)]), // 49
-            Intersection::new(vec![PathId(68), PathId(69)], vec![TileId(17)]), // 50
-            Intersection::new(vec![PathId(64), PathId(69), PathId(70)], vec![TileId(17), TileId(18)]), // 51
-            Intersection::new(vec![PathId(70), PathId(71)], vec![TileId(18)]), // 52
-            Intersection::new(vec![PathId(65), PathId(71)], vec![TileId(18)]), // 53
-        ];
+    pub fn new(tiles: Vec<Tile>) -> Board {
+        let harbors = Board::layout_of(&tiles).harbors();
+        Board::with_harbors(tiles, harbors)
+    }
+
+    /// Creates a new `Board` with the given tiles and harbors.
+    ///
+    /// # Arguments
+    /// - `tiles`: The tiles representing the game board resources and their dice values, in reading order.
+    /// - `harbors`: The harbors on the coast.
+    ///
+    /// # Panics
+    /// Panics if no layout has that many tiles.
+    pub fn with_harbors(tiles: Vec<Tile>, harbors: Vec<Harbor>) -> Board {
+        let layout = Board::layout_of(&tiles);
+        let Topology { paths, intersections } = Topology::from_tiles(&layout.coordinates());
+        let adjacency = Adjacency::new(&paths, &intersections, tiles.len());
 
         Self {
+            layout,
             paths,
             intersections,
             tiles,
+            harbors,
+            adjacency,
         }
     }
+
+    fn layout_of(tiles: &[Tile]) -> Layout {
+        Layout::for_tiles(tiles.len()).expect("A board has 19 or 30 tiles")
+    }
+
+    /// Returns the tiles touching `intersection`, in ascending order.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// for tile in board.tiles_of(IntersectionId(10)) {
+    ///     println!("{:?}", board.tiles[tile.0].kind);
+    /// }
+    /// ```
+    pub fn tiles_of(&self, intersection: IntersectionId) -> &[TileId] {
+        &self.intersections[intersection.0].tiles
+    }
+
+    /// Returns the six intersections at the corners of `tile`, in ascending order.
+    pub fn corners_of(&self, tile: TileId) -> &[IntersectionId] {
+        &self.adjacency.corners[tile.0]
+    }
+
+    /// Returns the intersections one path away from `intersection`, in ascending order.
+    pub fn neighbours_of(&self, intersection: IntersectionId) -> &[IntersectionId] {
+        &self.adjacency.neighbours[intersection.0]
+    }
+
+    /// Returns the paths ending at `intersection`, in ascending order.
+    pub fn paths_of(&self, intersection: IntersectionId) -> &[PathId] {
+        &self.adjacency.paths[intersection.0]
+    }
+
+    /// Returns the path joining two intersections, or `None` if they are not neighbours.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let path = board.path_between(IntersectionId(0), IntersectionId(1));
+    /// assert_eq!(path, Some(PathId(0)));
+    /// ```
+    pub fn path_between(&self, a: IntersectionId, b: IntersectionId) -> Option<PathId> {
+        self.adjacency.paths[a.0].iter()
+            .copied()
+            .find(|path| {
+                let Path(x, y) = self.paths[path.0];
+                (x, y) == (a, b) || (x, y) == (b, a)
+            })
+    }
 }
 
 /// Represents the state of the game, including:
+/// - `players`: The players in the game, in turn order.
 /// - `buildings`: A list of all buildings on the board.
 /// - `roads`: A list of all roads on the board.
 /// - `robber`: The current position of the robber.
+/// - `resources`: The resource cards held by each player.
+/// - `bank`: The cards left in the bank.
+/// - `turn`: Whose turn it is and the phase of that turn.
+/// - `rng`: The random number generator used for dice rolls.
+/// - `victory_target`: The number of victory points needed to win.
+/// - `longest_road_holder`: The player holding the Longest Road card, if any.
+/// - `largest_army_holder`: The player holding the Largest Army card, if any.
+/// - `played_knights`: One entry per knight card played, naming the player who played it.
+/// - `development_cards`: The development cards held by the players and not played yet.
+#[derive(Clone)]
 pub struct State {
+    pub players: Vec<Player>,
     pub buildings: Vec<Building>,
     pub roads: Vec<Road>,
     pub robber: RobberId,
     pub resources: PlayerResourceCount,
+    pub bank: Bank,
+    pub turn: Turn,
+    pub rng: Rng,
+    pub victory_target: u8,
+    pub longest_road_holder: Option<Player>,
+    pub largest_army_holder: Option<Player>,
+    pub played_knights: Vec<Player>,
+    pub development_cards: Vec<HeldCard>,
+}
+
+impl State {
+    /// Creates a game state from the pieces on the board.
+    ///
+    /// The state starts with the first player about to roll the dice. The bank holds every card
+    /// not held by a player and the unshuffled development deck; call `shuffle_development_deck`
+    /// once the generator is seeded.
+    /// The ASCII format does not record award holders; they are assigned by the next road or building placed,
+    /// or by calling `Game::update_longest_road` and `Game::update_largest_army`.
+    ///
+    /// # Panics
+    /// Panics if `players` is empty.
+    pub fn new(layout: Layout, players: Vec<Player>, buildings: Vec<Building>, roads: Vec<Road>, robber: RobberId, resources: PlayerResourceCount) -> State {
+        State {
+            turn: Turn::new(players[0]),
+            players,
+            buildings,
+            roads,
+            robber,
+            bank: Bank::without(layout, resources.sum()),
+            resources,
+            rng: Rng::default(),
+            victory_target: VICTORY_TARGET,
+            longest_road_holder: None,
+            largest_army_holder: None,
+            played_knights: vec![],
+            development_cards: vec![],
+        }
+    }
+
+    /// Shuffles the development deck of the bank with the game's generator.
+    pub fn shuffle_development_deck(&mut self) {
+        self.rng.shuffle(&mut self.bank.development);
+    }
+
+    /// Returns the player whose turn follows the turn of `player`.
+    pub fn next_player(&self, player: Player) -> Player {
+        let index = self.players.iter().position(|other| *other == player).unwrap_or(0);
+        self.players[(index + 1) % self.players.len()]
+    }
 }
 
 /// Represents the overall game state, including the board and the state of all players.
+#[derive(Clone)]
 pub struct Game {
     pub board: Board,
     pub state: State,
 }
 
+impl Game {
+    /// Creates a new game on `board`, at the start of the setup phase.
+    ///
+    /// Four players play on the standard board and six on the extension board.
+    /// The robber starts on the desert, and `seed` initializes the dice and the order of the development deck.
+    pub fn new(board: Board, seed: u64) -> Game {
+        let players = board.layout.players().to_vec();
+        Game::with_players(board, players, seed)
+    }
+
+    /// Creates a new game on `board` for `players`, who take their turns in the given order.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let game = Game::with_players(board, Player::THREE.to_vec(), 42);
+    /// ```
+    pub fn with_players(board: Board, players: Vec<Player>, seed: u64) -> Game {
+        let robber = board.tiles.iter()
+            .position(|tile| tile.kind == TileKind::Nothing)
+            .unwrap_or(0);
+        let mut state = State::new(board.layout, players, vec![], vec![], RobberId(robber), PlayerResourceCount::default());
+        state.turn = Turn::setup(&state.players);
+        state.rng = Rng::new(seed);
+        state.shuffle_development_deck();
+        Game { board, state }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::game::board::*;
+
+/// The number of knight cards in the development deck.
+pub const KNIGHT_CARDS: usize = 14;
+
+/// The number of victory point cards in the development deck.
+pub const VICTORY_POINT_CARDS: usize = 5;
+
+/// The number of cards of each progress card (road building, year of plenty, monopoly) in the development deck.
+pub const PROGRESS_CARDS: usize = 2;
+
+/// The number of knight cards in the development deck of the 5-6 player extension.
+pub const EXTENSION_KNIGHT_CARDS: usize = 20;
+
+/// The number of cards of each progress card in the development deck of the 5-6 player extension.
+pub const EXTENSION_PROGRESS_CARDS: usize = 3;
+
+/// An enumeration of the development card types.
+///
+/// - `Knight`: Moves the robber and counts towards the Largest Army.
+/// - `VictoryPoint`: Worth one victory point; it is never played.
+/// - `RoadBuilding`: Builds two roads for free.
+/// - `YearOfPlenty`: Takes any two resource cards from the bank.
+/// - `Monopoly`: Takes every card of one resource from the other players.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DevelopmentCard {
+    Knight,
+    VictoryPoint,
+    RoadBuilding,
+    YearOfPlenty,
+    Monopoly,
+}
+
+/// Represents a development card held by a player.
+///
+/// `bought_turn` is the `Turn.number` in which the card was bought; a card cannot be
+/// played in the turn it was bought.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HeldCard {
+    pub player: Player,
+    pub card: DevelopmentCard,
+    pub bought_turn: u32,
+}
+
+/// Returns the cards of the development deck of `layout`, unshuffled: 25 cards for the
+/// standard board and 34 for the 5-6 player extension.
+pub fn development_deck(layout: Layout) -> Vec<DevelopmentCard> {
+    let (knights, progress) = match layout {
+        Layout::Standard => (KNIGHT_CARDS, PROGRESS_CARDS),
+        Layout::Extension => (EXTENSION_KNIGHT_CARDS, EXTENSION_PROGRESS_CARDS),
+    };
+    let mut deck = vec![DevelopmentCard::Knight; knights];
+    deck.extend(vec![DevelopmentCard::VictoryPoint; VICTORY_POINT_CARDS]);
+    deck.extend(vec![DevelopmentCard::RoadBuilding; progress]);
+    deck.extend(vec![DevelopmentCard::YearOfPlenty; progress]);
+    deck.extend(vec![DevelopmentCard::Monopoly; progress]);
+    deck
+}
+
+impl Game {
+    /// Returns the development cards held by a player, in the order they were bought.
+    pub fn development_cards(&self, player: Player) -> Vec<DevelopmentCard> {
+        self.state.development_cards.iter()
+            .filter(|held| held.player == player)
+            .map(|held| held.card)
+            .collect()
+    }
+
+    /// Returns the kinds of development cards a player may play right now.
+    ///
+    /// Only one development card may be played per turn, a card cannot be played in the
+    /// turn it was bought, and victory point cards are never played. The phase of the turn
+    /// is not checked here.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if game.playable_development_cards(Player::Red).contains(&DevelopmentCard::Knight) {
+    ///     game.apply(Action::PlayKnight { player: Player::Red })?;
+    /// }
+    /// ```
+    pub fn playable_development_cards(&self, player: Player) -> Vec<DevelopmentCard> {
+        let turn = &self.state.turn;
+        if turn.player != player || turn.development_played {
+            return vec![];
+        }
+        let mut cards: Vec<DevelopmentCard> = vec![];
+        for held in self.state.development_cards.iter() {
+            if held.player == player
+                && held.card != DevelopmentCard::VictoryPoint
+                && held.bought_turn < turn.number
+                && !cards.contains(&held.card) {
+                cards.push(held.card);
+            }
+        }
+        cards
+    }
+
+    /// Removes a playable card of kind `card` from the hand of `player`.
+    ///
+    /// Returns `false` without changing anything if the player holds no such playable card.
+    pub(crate) fn take_development_card(&mut self, player: Player, card: DevelopmentCard) -> bool {
+        if !self.playable_development_cards(player).contains(&card) {
+            return false;
+        }
+        let number = self.state.turn.number;
+        let index = self.state.development_cards.iter()
+            .position(|held| held.player == player && held.card == card && held.bought_turn < number)
+            .unwrap();
+        self.state.development_cards.remove(index);
+        self.state.turn.development_played = true;
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::dice::Rng;
+
+    #[test]
+    fn test_development_deck() {
+        let deck = development_deck(Layout::Standard);
+        assert_eq!(deck.len(), 25);
+        assert_eq!(deck.iter().filter(|card| **card == DevelopmentCard::Knight).count(), 14);
+        assert_eq!(deck.iter().filter(|card| **card == DevelopmentCard::VictoryPoint).count(), 5);
+
+        let mut shuffled = deck.clone();
+        Rng::new(7).shuffle(&mut shuffled);
+        assert_ne!(shuffled, deck);
+        assert_eq!(shuffled.iter().filter(|card| **card == DevelopmentCard::Monopoly).count(), 2);
+
+        let deck = development_deck(Layout::Extension);
+        assert_eq!(deck.len(), 34);
+        assert_eq!(deck.iter().filter(|card| **card == DevelopmentCard::Knight).count(), 20);
+    }
+}
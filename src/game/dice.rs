@@ -0,0 +1,70 @@
+/// The seed used when a game is created without an explicit one.
+const DEFAULT_SEED: u64 = 0x2545_F491_4F6C_DD1D;
+
+/// A small seeded pseudo random number generator (xorshift64*).
+///
+/// The generator is part of the game `State`, so a game can be replayed exactly
+/// from its seed and cloned games roll the same dice.
+///
+/// Example usage:
+/// ```no_run
+/// let mut rng = Rng::new(42);
+/// let roll = rng.roll();
+/// assert!((2..=12).contains(&roll));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rng(u64);
+
+impl Rng {
+    /// Creates a new generator from `seed`. A zero seed is replaced by a fixed default.
+    pub fn new(seed: u64) -> Rng {
+        Rng(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    /// Returns the next pseudo random 64-bit number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a pseudo random number in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Shuffles `items` in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+
+    /// Rolls two six-sided dice and returns their sum.
+    pub fn roll(&mut self) -> u8 {
+        (self.below(6) + self.below(6) + 2) as u8
+    }
+}
+
+/// Returns in how many of the 36 outcomes of two six-sided dice the sum is `roll`.
+///
+/// Example usage:
+/// ```no_run
+/// assert_eq!(roll_ways(7), 6);
+/// assert_eq!(roll_ways(12), 1);
+/// ```
+pub fn roll_ways(roll: u8) -> u8 {
+    if (2..=12).contains(&roll) {
+        6 - (roll as i8 - 7).unsigned_abs()
+    } else {
+        0
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Rng::new(DEFAULT_SEED)
+    }
+}
@@ -1,7 +1,31 @@
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use crate::game::board::*;
-use crate::game::resources::{PlayerResourceCount, ResourceCount};
+use crate::game::development::{DevelopmentCard, HeldCard};
+use crate::game::resources::{Bank, PlayerResourceCount, ResourceCount, RESOURCES};
+use crate::game::trade::TradeOffer;
+use crate::game::turn::{setup_steps, Phase, Turn};
+
+/// The header of the resource table appended below the board.
+const RESOURCE_HEADER: &str = "   G  W  B  L  O";
+
+/// The marker starting the harbor line below the board.
+const HARBOR_MARKER: &str = "H";
+
+/// The marker starting the seat line below the board.
+const SEATS_MARKER: &str = "P";
+
+/// The marker starting the turn line below the board.
+pub(crate) const TURN_MARKER: &str = "T";
+
+/// The marker starting the line of a player's development cards below the board.
+const DEVELOPMENT_MARKER: &str = "D";
+
+/// The marker starting the bank line below the board.
+const BANK_MARKER: &str = "X";
+
+/// The order in which players are listed in the resource table.
+const RESOURCE_ORDER: [Player; 6] = [Player::White, Player::Red, Player::Blue, Player::Orange, Player::Green, Player::Brown];
 
 /// A constant ASCII template representing the Settlers of Catan game board layout.
 ///
@@ -30,6 +54,41 @@ BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB
           *   TTTT  *   TTTT  *   TTTT  *
           BB * BB * BB * BB * BB * BB * BB";
 
+/// The ASCII template of the 5-6 player extension board, drawn like `TEMPLATE` with rows of
+/// 3, 4, 5, 6, 5, 4 and 3 tiles.
+const EXTENSION_TEMPLATE: &str = "
+               BB * BB * BB * BB * BB * BB * BB
+               *   TTTT  *   TTTT  *   TTTT  *
+          BB * BB * BB * BB * BB * BB * BB * BB * BB
+          *   TTTT  *   TTTT  *   TTTT  *   TTTT  *
+     BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB
+     *   TTTT  *   TTTT  *   TTTT  *   TTTT  *   TTTT  *
+BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB
+*   TTTT  *   TTTT  *   TTTT  *   TTTT  *   TTTT  *   TTTT  *
+BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB
+     *   TTTT  *   TTTT  *   TTTT  *   TTTT  *   TTTT  *
+     BB * BB * BB * BB * BB * BB * BB * BB * BB * BB * BB
+          *   TTTT  *   TTTT  *   TTTT  *   TTTT  *
+          BB * BB * BB * BB * BB * BB * BB * BB * BB
+               *   TTTT  *   TTTT  *   TTTT  *
+               BB * BB * BB * BB * BB * BB * BB";
+
+/// Returns the ASCII template of `layout`.
+fn template(layout: Layout) -> &'static str {
+    match layout {
+        Layout::Standard => TEMPLATE,
+        Layout::Extension => EXTENSION_TEMPLATE,
+    }
+}
+
+/// Recognises the layout of an ASCII board by the indentation of its first row of intersections.
+fn layout_of(board_str: &str) -> Result<Layout, &'static str> {
+    let indentation = |text: &str| text.lines().nth(1).map(|line| line.len() - line.trim_start().len());
+    [Layout::Standard, Layout::Extension].iter().copied()
+        .find(|layout| indentation(template(*layout)) == indentation(board_str))
+        .ok_or("The board does not match a known layout")
+}
+
 
 /// Converts a `TileKind` to its corresponding character representation.
 ///
@@ -109,6 +168,44 @@ impl TryFrom<char> for TileKind {
 }
 
 
+/// Converts a `HarborKind` to its corresponding character representation.
+///
+/// A 3:1 harbor is written as `'?'`, a 2:1 harbor as the character of its resource.
+///
+/// Example usage:
+/// ```no_run
+/// let kind = HarborKind::Resource(TileKind::Ore);
+/// assert_eq!(char::from(kind), 'O');
+/// ```
+impl From<HarborKind> for char {
+    fn from(kind: HarborKind) -> Self {
+        match kind {
+            HarborKind::Generic => '?',
+            HarborKind::Resource(resource) => resource.into(),
+        }
+    }
+}
+
+
+/// Attempts to convert a character into a `HarborKind`.
+///
+/// `'?'` is a 3:1 harbor; the characters of the five resources are 2:1 harbors.
+/// Returns an error string for any other character, including the desert `'N'`.
+impl TryFrom<char> for HarborKind {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '?' => Ok(HarborKind::Generic),
+            'N' => Err("Invalid character for HarborKind"),
+            _ => TileKind::try_from(c)
+                .map(HarborKind::Resource)
+                .map_err(|_| "Invalid character for HarborKind"),
+        }
+    }
+}
+
+
 /// Attempts to convert a character into a `BuildingKind`.
 ///
 /// This implementation maps specific characters to their corresponding `BuildingKind` enum:
@@ -145,6 +242,9 @@ impl TryFrom<char> for BuildingKind {
 /// - `Player::Red` -> `'R'`
 /// - `Player::Blue` -> `'B'`
 /// - `Player::White` -> `'W'`
+/// - `Player::Orange` -> `'O'`
+/// - `Player::Green` -> `'G'`
+/// - `Player::Brown` -> `'N'`
 ///
 /// Example usage:
 /// ```no_run
@@ -158,6 +258,46 @@ impl From<Player> for char {
             Player::Red => 'R',
             Player::Blue => 'B',
             Player::White => 'W',
+            Player::Orange => 'O',
+            Player::Green => 'G',
+            Player::Brown => 'N',
+        }
+    }
+}
+
+
+/// Converts a `DevelopmentCard` to its corresponding character representation.
+///
+/// - `DevelopmentCard::Knight` -> `'K'`
+/// - `DevelopmentCard::VictoryPoint` -> `'V'`
+/// - `DevelopmentCard::RoadBuilding` -> `'R'`
+/// - `DevelopmentCard::YearOfPlenty` -> `'Y'`
+/// - `DevelopmentCard::Monopoly` -> `'M'`
+impl From<DevelopmentCard> for char {
+    fn from(card: DevelopmentCard) -> Self {
+        match card {
+            DevelopmentCard::Knight => 'K',
+            DevelopmentCard::VictoryPoint => 'V',
+            DevelopmentCard::RoadBuilding => 'R',
+            DevelopmentCard::YearOfPlenty => 'Y',
+            DevelopmentCard::Monopoly => 'M',
+        }
+    }
+}
+
+
+/// Attempts to convert a character into a `DevelopmentCard`, the inverse of `From<DevelopmentCard> for char`.
+impl TryFrom<char> for DevelopmentCard {
+    type Error = &'static str;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'K' => Ok(DevelopmentCard::Knight),
+            'V' => Ok(DevelopmentCard::VictoryPoint),
+            'R' => Ok(DevelopmentCard::RoadBuilding),
+            'Y' => Ok(DevelopmentCard::YearOfPlenty),
+            'M' => Ok(DevelopmentCard::Monopoly),
+            _ => Err("Invalid character for DevelopmentCard"),
         }
     }
 }
@@ -172,7 +312,8 @@ impl From<Player> for char {
 /// - `Self::Error`: The error type, which is a static string slice (`&'static str`).
 ///
 /// # Arguments
-/// - `board_str`: A string representing the state of the game, based on the `TEMPLATE`.
+/// - `board_str`: A string representing the state of the game, based on the `TEMPLATE` or,
+///   for the 5-6 player extension, on the `EXTENSION_TEMPLATE`.
 ///
 /// # Returns
 /// A `Result` containing the parsed `Game` object if successful, or an error message if the input is invalid.
@@ -181,6 +322,21 @@ impl From<Player> for char {
 /// - Parses `building_coordinates` to identify building positions and their attributes.
 /// - Parses `tile_coordinates` to extract tile dice values and resources.
 /// - Parses `road_coordinates` to identify the location and ownership of roads.
+/// - Parses the optional harbor line below the board, a list of `<intersection>-<intersection>:<kind>`
+///   entries after `H`; without it the board gets the `STANDARD_HARBORS`.
+/// - Parses the optional resource table below the board; players without a line hold no resources.
+///   Every resource count, in the table, the bank line or a trade offer, must be between zero and
+///   `Layout::bank_resources`, and the players together cannot hold more cards of a resource
+///   than the bank starts with.
+/// - Parses the optional turn line `T <player> <phase> <number> <rolled> <played>`; without it the
+///   first player is about to roll the dice. The players of the turn must be seated, and a setup turn
+///   must name a step of the setup phase and a settlement of the turn player.
+/// - Parses the optional development lines `D <player> <knights played> <card><bought turn>...`.
+/// - Parses the optional bank line `X <grain> <wool> <brick> <lumber> <ore> <deck>`; without it the
+///   bank holds the cards nobody holds and a shuffled deck without the held development cards.
+/// - Parses the optional seat line `P <player>...`, listing the players in turn order; every
+///   player owning a piece or having a resource line must be seated, and at least three players
+///   must be listed. Without it, the three players of `Player::THREE` play.
 /// - Ensures that the number of parsed elements matches the expected counts defined by constants
 ///   (e.g., `INTERSECTIONS`, `TILES`, `PATHS`).
 ///
@@ -208,9 +364,11 @@ impl From<Player> for char {
 /// # Errors
 /// - Returns an error if the string does not match the expected format or fails during parsing.
 /// - Ensures that mandatory elements such as buildings, tiles, and roads are properly defined.
+/// - Returns an error if the board is missing rows, a row is too short, a dice number does not
+///   parse, or no tile holds the robber; malformed input never panics.
 ///
 /// # Notes
-/// - The template used for parsing is defined in the constant `TEMPLATE`.
+/// - The template used for parsing is chosen by the indentation of the first row of intersections.
 /// - Any discrepancies in the string's structure or missing elements will result in an error.
 impl TryFrom<String> for Game {
     type Error = &'static str;
@@ -219,7 +377,9 @@ impl TryFrom<String> for Game {
         let mut building_coordinates = vec![];
         let mut tile_coordinates = vec![];
         let mut road_coordinates = vec![];
-        for line in TEMPLATE.lines() {
+        let layout = layout_of(&board_str)?;
+        let template = template(layout);
+        for line in template.lines() {
             let line = line.trim_end();
             let mut building_line = vec![];
             let mut tile_line = vec![];
@@ -242,18 +402,27 @@ impl TryFrom<String> for Game {
             road_coordinates.push(road_line);
         }
 
-        assert_eq!(building_coordinates.iter().map(|c| c.len()).sum::<usize>(), INTERSECTIONS);
-        assert_eq!(tile_coordinates.iter().map(|t| t.len()).sum::<usize>(), TILES);
-        assert_eq!(road_coordinates.iter().map(|t| t.len()).sum::<usize>(), PATHS);
+        let (intersections, tiles, paths) = match layout {
+            Layout::Standard => (INTERSECTIONS, TILES, PATHS),
+            Layout::Extension => (EXTENSION_INTERSECTIONS, EXTENSION_TILES, EXTENSION_PATHS),
+        };
+        if building_coordinates.iter().map(|c| c.len()).sum::<usize>() != intersections
+            || tile_coordinates.iter().map(|t| t.len()).sum::<usize>() != tiles
+            || road_coordinates.iter().map(|t| t.len()).sum::<usize>() != paths {
+            return Err("The template does not match the layout");
+        }
+        let board_lines: Vec<Vec<char>> = board_str.lines().map(|line| line.chars().collect()).collect();
+        let line = |i: usize| board_lines.get(i).ok_or("The board is missing rows");
+        let cell = |chars: &[char], i: usize| chars.get(i).copied().ok_or("A row of the board is too short");
 
 
         let mut id = 0;
         let mut buildings: Vec<Building> = vec![];
         for (i, line_coordinates) in building_coordinates.iter().enumerate() {
-            let chars: Vec<char> = board_str.lines().nth(i).unwrap().chars().clone().collect();
+            let chars = line(i)?;
             for coordinate in line_coordinates {
-                let first_char = chars[*coordinate];
-                let second_char = chars[coordinate + 1];
+                let first_char = cell(chars, *coordinate)?;
+                let second_char = cell(chars, coordinate + 1)?;
                 if first_char != 'o' {
                     let building = Building{
                         intersection_id: IntersectionId(id),
@@ -270,9 +439,9 @@ impl TryFrom<String> for Game {
         let mut id = 0;
         let mut roads: Vec<Road> = vec![];
         for (i, line_coordinates) in road_coordinates.iter().enumerate() {
-            let chars: Vec<char> = board_str.lines().nth(i).unwrap().chars().clone().collect();
+            let chars = line(i)?;
             for coordinate in line_coordinates {
-                let first_char = chars[*coordinate];
+                let first_char = cell(chars, *coordinate)?;
                 if first_char != '.' {
                     let road = Road{
                         id: PathId(id),
@@ -290,73 +459,338 @@ impl TryFrom<String> for Game {
         let mut robber: Option<RobberId> = None;
 
         for (i, line_coordinates) in tile_coordinates.iter().enumerate() {
-            let chars: Vec<char> = board_str.lines().nth(i).unwrap().chars().clone().collect();
+            let chars = line(i)?;
             for coordinate in line_coordinates {
-                let first_char = chars[*coordinate];
-                let second_char = chars[coordinate + 1];
-                let third_char = chars[coordinate + 2];
-                let fourth_char = chars[coordinate + 3];
+                let first_char = cell(chars, *coordinate)?;
+                let second_char = cell(chars, coordinate + 1)?;
+                let third_char = cell(chars, coordinate + 2)?;
+                let fourth_char = cell(chars, coordinate + 3)?;
                 if fourth_char == '!' {
                     robber = Some(RobberId(id))
                 }
                 let kind: TileKind = TileKind::try_from(third_char)?;
 
-                let dice = format!("{}{}", first_char, second_char).parse::<u8>().expect("Invalid tile dice number");
+                let dice = format!("{}{}", first_char, second_char).parse::<u8>().map_err(|_| "Invalid tile dice number")?;
                 tiles.push(Tile{ dice, kind });
                 id += 1;
             }
         }
 
-        let resource_lines: Vec<&str> = board_str.lines().clone()
-            .filter(|p| p.starts_with("W") || p.starts_with("B") || p.starts_with("R"))
-            .collect();
+        let harbors = match board_str.lines()
+            .skip(template.lines().count())
+            .find(|line| line.split_whitespace().next() == Some(HARBOR_MARKER)) {
+            Some(line) => parse_harbors(line, intersections)?,
+            None => layout.harbors(),
+        };
+        let board:  Board = Board::with_harbors(tiles, harbors);
 
         // G  W  B  L  O
-        let white = resource_lines[0].split_whitespace().skip(1).map(|s| s.parse::<i8>().unwrap()).collect::<Vec<_>>();
-        let red = resource_lines[1].split_whitespace().skip(1).map(|s| s.parse::<i8>().unwrap()).collect::<Vec<_>>();
-        let blue = resource_lines[2].split_whitespace().skip(1).map(|s| s.parse::<i8>().unwrap()).collect::<Vec<_>>();
-        let board:  Board = Board::new(tiles.try_into().expect("The board has not exactly 19 tiles"));
-
-        let resources = PlayerResourceCount{
-            red: ResourceCount{
-                grain: red[0],
-                wool: red[1],
-                brick: red[2],
-                lumber: red[3],
-                ore: red[4],
-            },
-            blue: ResourceCount{
-                grain: blue[0],
-                wool: blue[1],
-                brick: blue[2],
-                lumber: blue[3],
-                ore: blue[4],
-            },
-            white: ResourceCount{
-                grain: white[0],
-                wool: white[1],
-                brick: white[2],
-                lumber: white[3],
-                ore: white[4],
-            },
+        let mut resources = PlayerResourceCount::default();
+        let mut players: Vec<Player> = buildings.iter().map(|building| building.player)
+            .chain(roads.iter().map(|road| road.player))
+            .collect();
+        let extra_lines: Vec<&str> = board_str.lines().skip(template.lines().count()).collect();
+        for line in extra_lines.iter() {
+            if line.trim() == RESOURCE_HEADER.trim() {
+                continue;
+            }
+            let mut cells = line.split_whitespace();
+            let player = match cells.next().and_then(|c| c.chars().next()) {
+                Some(c) => match Player::try_from(c) {
+                    Ok(player) => player,
+                    Err(_) => continue,
+                },
+                None => continue,
+            };
+            let counts = cells.map(|s| s.parse::<i8>().map_err(|_| "Invalid resource count"))
+                .collect::<Result<Vec<_>, _>>()?;
+            if counts.len() != 5 {
+                return Err("A resource line must have exactly 5 counts");
+            }
+            let count = in_supply(ResourceCount {
+                grain: counts[0],
+                wool: counts[1],
+                brick: counts[2],
+                lumber: counts[3],
+                ore: counts[4],
+            }, layout.bank_resources())?;
+            let held = resources.sum() - resources[player];
+            if RESOURCES.iter().any(|kind| held[*kind] + count[*kind] > layout.bank_resources()) {
+                return Err("More resource cards are held than the bank has");
+            }
+            players.push(player);
+            resources[player] = count;
+        }
+
+        let seats = match marked_lines(&extra_lines, SEATS_MARKER).next() {
+            Some(line) => parse_seats(line)?,
+            None => Player::THREE.to_vec(),
+        };
+        let players = check_seats(seats, &players)?;
+        let robber = robber.ok_or("The board has no robber")?;
+        let mut state = State::new(layout, players, buildings, roads, robber, resources);
+
+        if let Some(line) = marked_lines(&extra_lines, TURN_MARKER).next() {
+            state.turn = parse_turn(line, layout.bank_resources())?;
+            check_turn(&state, intersections)?;
+        }
+        for line in marked_lines(&extra_lines, DEVELOPMENT_MARKER) {
+            let (player, knights, cards) = parse_development(line)?;
+            if !state.players.contains(&player) {
+                return Err("A development card holder is not in the game");
+            }
+            state.played_knights.extend(std::iter::repeat(player).take(knights));
+            state.development_cards.extend(cards);
+        }
+        match marked_lines(&extra_lines, BANK_MARKER).next() {
+            Some(line) => state.bank = parse_bank(line, layout.bank_resources())?,
+            None => {
+                for held in state.development_cards.iter() {
+                    let index = state.bank.development.iter().position(|card| *card == held.card)
+                        .ok_or("More development cards are held than the deck has")?;
+                    state.bank.development.remove(index);
+                }
+                state.shuffle_development_deck();
+            }
+        }
+
+        Ok(Game { board, state })
+    }
+}
+
+/// Parses a harbor line such as `H  0-1:?  3-4:G`.
+fn parse_harbors(line: &str, intersections: usize) -> Result<Vec<Harbor>, &'static str> {
+    let mut harbors = vec![];
+    for entry in line.split_whitespace().skip(1) {
+        let (pair, kind) = entry.split_once(':').ok_or("A harbor must be written as <a>-<b>:<kind>")?;
+        let (a, b) = pair.split_once('-').ok_or("A harbor must be written as <a>-<b>:<kind>")?;
+        let a = a.parse::<usize>().map_err(|_| "Invalid harbor intersection")?;
+        let b = b.parse::<usize>().map_err(|_| "Invalid harbor intersection")?;
+        if a >= intersections || b >= intersections {
+            return Err("Invalid harbor intersection");
+        }
+        let mut chars = kind.chars();
+        let kind = match (chars.next(), chars.next()) {
+            (Some(c), None) => HarborKind::try_from(c)?,
+            _ => return Err("Invalid character for HarborKind"),
         };
+        harbors.push(Harbor { intersections: (IntersectionId(a), IntersectionId(b)), kind });
+    }
+    Ok(harbors)
+}
+
+/// Returns the lines below the board that start with `marker`.
+fn marked_lines<'a>(lines: &'a [&'a str], marker: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    lines.iter().copied().filter(move |line| line.split_whitespace().next() == Some(marker))
+}
+
+/// Parses a single character naming a player.
+fn parse_player(token: &str) -> Result<Player, &'static str> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Player::try_from(c),
+        _ => Err("Invalid character for Player"),
+    }
+}
+
+/// Parses a seat line such as `P  W  R  B`, listing the players in turn order.
+fn parse_seats(line: &str) -> Result<Vec<Player>, &'static str> {
+    line.split_whitespace().skip(1).map(parse_player).collect()
+}
+
+/// Checks the seats of a game, which are `Player::THREE` when the board has no seat line.
+///
+/// Every player in `present`, owning a piece or having a resource line, must be seated, and a game
+/// needs at least three players.
+fn check_seats(seats: Vec<Player>, present: &[Player]) -> Result<Vec<Player>, &'static str> {
+    if seats.iter().enumerate().any(|(i, player)| seats[..i].contains(player)) {
+        return Err("A player is seated twice");
+    }
+    if present.iter().any(|player| !seats.contains(player)) {
+        return Err("A player in the game is not seated");
+    }
+    if seats.len() < Player::THREE.len() {
+        return Err("A game needs at least three players");
+    }
+    Ok(seats)
+}
+
+/// Parses a list of players written as their characters, such as `RB`.
+fn parse_players(token: &str) -> Result<Vec<Player>, &'static str> {
+    token.chars().map(Player::try_from).collect()
+}
+
+fn players_token(players: &[Player]) -> String {
+    players.iter().map(|player| char::from(*player)).collect()
+}
+
+/// Parses resource counts written as `grain,wool,brick,lumber,ore`, each between zero and the
+/// `supply` of the bank.
+fn parse_count(token: &str, supply: i8) -> Result<ResourceCount, &'static str> {
+    let counts = token.split(',')
+        .map(|count| count.parse::<i8>().map_err(|_| "Invalid resource count"))
+        .collect::<Result<Vec<_>, _>>()?;
+    match counts[..] {
+        [grain, wool, brick, lumber, ore] => in_supply(ResourceCount { grain, wool, brick, lumber, ore }, supply),
+        _ => Err("A resource count must have exactly 5 numbers"),
+    }
+}
+
+/// Checks that every count is between zero and the `supply` of the bank, so that adding up the
+/// parsed counts cannot overflow.
+fn in_supply(count: ResourceCount, supply: i8) -> Result<ResourceCount, &'static str> {
+    if RESOURCES.iter().all(|kind| (0..=supply).contains(&count[*kind])) {
+        Ok(count)
+    } else {
+        Err("Invalid resource count")
+    }
+}
+
+fn count_token(count: &ResourceCount) -> String {
+    format!("{},{},{},{},{}", count.grain, count.wool, count.brick, count.lumber, count.ore)
+}
+
+/// Writes a phase as one token: `setup:<step>[:<settlement>]`, `roll`, `main`, `discard:<players>`,
+/// `robber`, `roads:<left>`, `trade:<from>:<to or ->:<give>:<get>:<waiting>`, `special:<players>`
+/// or `over`.
+fn phase_token(phase: &Phase) -> String {
+    match phase {
+        Phase::Setup { step, settlement: None } => format!("setup:{}", step),
+        Phase::Setup { step, settlement: Some(IntersectionId(id)) } => format!("setup:{}:{}", step, id),
+        Phase::Roll => "roll".to_string(),
+        Phase::Main => "main".to_string(),
+        Phase::Discard { players } => format!("discard:{}", players_token(players)),
+        Phase::MoveRobber => "robber".to_string(),
+        Phase::RoadBuilding { roads } => format!("roads:{}", roads),
+        Phase::Trade { offer, waiting } => format!("trade:{}:{}:{}:{}:{}",
+                                                   char::from(offer.from),
+                                                   offer.to.map_or('-', char::from),
+                                                   count_token(&offer.give),
+                                                   count_token(&offer.get),
+                                                   players_token(waiting)),
+        Phase::SpecialBuilding { players } => format!("special:{}", players_token(players)),
+        Phase::GameOver => "over".to_string(),
+    }
+}
+
+/// Parses a phase written by `phase_token`, with the resource counts of a trade offer bounded by
+/// the `supply` of the bank.
+fn parse_phase(token: &str, supply: i8) -> Result<Phase, &'static str> {
+    let fields: Vec<&str> = token.split(':').collect();
+    let number = |field: &str| field.parse::<usize>().map_err(|_| "Invalid number in phase");
+    match fields[..] {
+        ["setup", step] => Ok(Phase::Setup { step: number(step)?, settlement: None }),
+        ["setup", step, settlement] => Ok(Phase::Setup { step: number(step)?, settlement: Some(IntersectionId(number(settlement)?)) }),
+        ["roll"] => Ok(Phase::Roll),
+        ["main"] => Ok(Phase::Main),
+        ["discard", players] => Ok(Phase::Discard { players: parse_players(players)? }),
+        ["robber"] => Ok(Phase::MoveRobber),
+        ["roads", roads] => Ok(Phase::RoadBuilding { roads: roads.parse().map_err(|_| "Invalid number in phase")? }),
+        ["trade", from, to, give, get, waiting] => Ok(Phase::Trade {
+            offer: TradeOffer {
+                from: parse_player(from)?,
+                to: if to == "-" { None } else { Some(parse_player(to)?) },
+                give: parse_count(give, supply)?,
+                get: parse_count(get, supply)?,
+            },
+            waiting: parse_players(waiting)?,
+        }),
+        ["special", players] => Ok(Phase::SpecialBuilding { players: parse_players(players)? }),
+        ["over"] => Ok(Phase::GameOver),
+        _ => Err("Invalid phase"),
+    }
+}
 
-        Ok(
-            Game{ board,
-                state: State {
-                    buildings,
-                    roads,
-                    robber: robber.unwrap(),
-                    resources,
-                } }
-        )
+/// Parses a flag written as `0` or `1`.
+fn parse_flag(token: &str) -> Result<bool, &'static str> {
+    match token {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err("A flag must be 0 or 1"),
+    }
+}
+
+/// Parses a turn line such as `T  R  main  3  1  0`: the player, the phase, the turn number and
+/// whether the dice were rolled and a development card was played in this turn.
+fn parse_turn(line: &str, supply: i8) -> Result<Turn, &'static str> {
+    match line.split_whitespace().skip(1).collect::<Vec<_>>()[..] {
+        [player, phase, number, rolled, development_played] => Ok(Turn {
+            player: parse_player(player)?,
+            phase: parse_phase(phase, supply)?,
+            number: number.parse().map_err(|_| "Invalid turn number")?,
+            rolled: parse_flag(rolled)?,
+            development_played: parse_flag(development_played)?,
+        }),
+        _ => Err("A turn line must be written as T <player> <phase> <number> <rolled> <played>"),
+    }
+}
+
+/// Checks that the parsed turn fits the game: its players are seated and a setup turn points at
+/// a step and a settlement of the game.
+fn check_turn(state: &State, intersections: usize) -> Result<(), &'static str> {
+    let turn = &state.turn;
+    if !state.players.contains(&turn.player) {
+        return Err("The turn player is not in the game");
+    }
+    let players: Vec<Player> = match &turn.phase {
+        Phase::Setup { step, settlement } => {
+            if *step >= setup_steps(&state.players) {
+                return Err("The setup step is past the end of the setup phase");
+            }
+            if let Some(settlement) = settlement {
+                if settlement.0 >= intersections {
+                    return Err("Invalid setup settlement");
+                }
+                if !state.buildings.iter().any(|building| building.intersection_id == *settlement && building.player == turn.player) {
+                    return Err("The setup settlement is not a building of the turn player");
+                }
+            }
+            vec![]
+        }
+        Phase::Discard { players } | Phase::SpecialBuilding { players } => players.clone(),
+        Phase::Trade { offer, waiting } => offer.to.iter().copied().chain(waiting.iter().copied()).chain([offer.from]).collect(),
+        Phase::Roll | Phase::Main | Phase::MoveRobber | Phase::RoadBuilding { .. } | Phase::GameOver => vec![],
+    };
+    if players.iter().any(|player| !state.players.contains(player)) {
+        return Err("A player in the phase is not in the game");
     }
+    Ok(())
+}
+
+/// Parses a development card line such as `D  R  2  K3  V0`: the player, the number of knights
+/// they played, and every card in their hand followed by the turn it was bought in.
+fn parse_development(line: &str) -> Result<(Player, usize, Vec<HeldCard>), &'static str> {
+    let mut cells = line.split_whitespace().skip(1);
+    let player = parse_player(cells.next().ok_or("A development line must name a player")?)?;
+    let knights = cells.next().ok_or("A development line must count the knights played")?
+        .parse::<usize>().map_err(|_| "Invalid number of knights played")?;
+    let cards = cells.map(|cell| {
+        let mut chars = cell.chars();
+        let card = DevelopmentCard::try_from(chars.next().ok_or("Invalid development card")?)?;
+        let bought_turn = chars.as_str().parse::<u32>().map_err(|_| "Invalid development card turn")?;
+        Ok(HeldCard { player, card, bought_turn })
+    }).collect::<Result<Vec<_>, _>>()?;
+    Ok((player, knights, cards))
+}
+
+/// Parses a bank line such as `X  19 19 19 19 19  KVM`: the resource cards of the bank and the
+/// development deck, bought from the end.
+fn parse_bank(line: &str, supply: i8) -> Result<Bank, &'static str> {
+    let cells: Vec<&str> = line.split_whitespace().skip(1).collect();
+    if cells.len() < 5 || cells.len() > 6 {
+        return Err("A bank line must be written as X <grain> <wool> <brick> <lumber> <ore> <deck>");
+    }
+    let resources = parse_count(&cells[..5].join(","), supply)?;
+    let development = cells.get(5).map_or(Ok(vec![]), |deck| {
+        deck.chars().map(DevelopmentCard::try_from).collect::<Result<Vec<_>, _>>()
+    })?;
+    Ok(Bank { resources, development })
 }
 
 /// Converts a `Game` object into a string representation.
 ///
 /// This implementation serializes the current state of the game into an ASCII representation
-/// based on the predefined `TEMPLATE`. The string output includes details about the tiles,
+/// based on the template of its layout. The string output includes details about the tiles,
 /// buildings, roads, and the position of the robber.
 ///
 /// # Arguments
@@ -369,6 +803,12 @@ impl TryFrom<String> for Game {
 /// - **Tiles**: Replaces `TTTT` placeholders in the template with dice values, tile types, and the robber position.
 /// - **Buildings**: Replaces `BB` placeholders with the player owning the building and the building type.
 /// - **Roads**: Replaces `*` placeholders with the player owning the road, or `.` if no road exists.
+/// - **Harbors**: Appends a line listing the harbors below the board.
+/// - **Seats**: Appends a line listing the players in turn order.
+/// - **Turn**: Appends a line with the player to act, the phase, the turn number and the turn's flags.
+/// - **Development cards**: Appends a line for every player holding or having played a development card.
+/// - **Bank**: Appends a line with the resource cards of the bank and its development deck.
+/// - **Resources**: Appends a resource table with one line per player in the game below the board.
 ///
 /// # Example
 /// ```rust
@@ -380,7 +820,7 @@ impl TryFrom<String> for Game {
 /// ```
 ///
 /// # Notes
-/// - The function uses the `TEMPLATE` constant to define the structure of the serialized string.
+/// - The function uses the `TEMPLATE` or `EXTENSION_TEMPLATE` constant to define the structure of the serialized string.
 /// - If a building or road is not present at a specific location, default placeholders (`oo` for buildings, `.` for roads) are used.
 ///
 /// # Implementation Details
@@ -388,14 +828,14 @@ impl TryFrom<String> for Game {
 /// - The robber's position is indicated with a `!` character appended to the tile description.
 impl From<Game> for String {
     fn from(game: Game) -> Self {
-        let mut output  = TEMPLATE.to_string();
+        let mut output  = template(game.board.layout).to_string();
         for (id, tile) in game.board.tiles.iter().enumerate() {
             let robber = if RobberId(id) == game.state.robber {
                 '!'
             } else {
                 ' '
             };
-            let kind = char::from(tile.kind.clone());
+            let kind = char::from(tile.kind);
             output = output.replacen("TTTT",  &format!("{:02}{}{}", tile.dice, kind, robber), 1);
         }
 
@@ -404,16 +844,12 @@ impl From<Game> for String {
             building_map.insert(&int.intersection_id, int);
         }
 
-        for i in 0..INTERSECTIONS {
+        for i in 0..game.board.intersections.len() {
             let cell = match building_map.get(&IntersectionId(i)) {
                 None => { "oo".to_string() }
                 Some(int) => {
                     let player: char = int.player.into();
-                    let kind = match int.kind {
-                        BuildingKind::Settlement =>{ "S".to_string() }
-                        BuildingKind::City => { "C".to_string() }
-                    };
-                    format!("{}{}", player, kind)
+                    format!("{}{}", player, int.kind.to_char())
                 }
             };
 
@@ -425,7 +861,7 @@ impl From<Game> for String {
             road_map.insert(&int.id, int);
         }
 
-        for i in 0..PATHS {
+        for i in 0..game.board.paths.len() {
             let cell = match road_map.get(&PathId(i)) {
                 None => { ".".to_string() }
                 Some(int) => char::from(int.player).into(),
@@ -434,6 +870,52 @@ impl From<Game> for String {
             output = output.replacen("*", &cell, 1);
         }
 
+        output.push('\n');
+        output.push_str(HARBOR_MARKER);
+        for harbor in game.board.harbors.iter() {
+            let (IntersectionId(a), IntersectionId(b)) = harbor.intersections;
+            output.push_str(&format!("  {}-{}:{}", a, b, char::from(harbor.kind)));
+        }
+
+        output.push('\n');
+        output.push_str(SEATS_MARKER);
+        for player in game.state.players.iter() {
+            output.push_str(&format!("  {}", char::from(*player)));
+        }
+
+        let turn = &game.state.turn;
+        output.push_str(&format!("\n{}  {}  {}  {}  {}  {}", TURN_MARKER, char::from(turn.player), phase_token(&turn.phase),
+                                 turn.number, turn.rolled as u8, turn.development_played as u8));
+
+        for player in game.state.players.iter() {
+            let knights = game.state.played_knights.iter().filter(|knight| *knight == player).count();
+            let cards: Vec<String> = game.state.development_cards.iter()
+                .filter(|held| held.player == *player)
+                .map(|held| format!("{}{}", char::from(held.card), held.bought_turn))
+                .collect();
+            if knights > 0 || !cards.is_empty() {
+                let line = format!("{}  {}  {}  {}", DEVELOPMENT_MARKER, char::from(*player), knights, cards.join("  "));
+                output.push('\n');
+                output.push_str(line.trim_end());
+            }
+        }
+
+        let bank = &game.state.bank.resources;
+        let deck: String = game.state.bank.development.iter().map(|card| char::from(*card)).collect();
+        let line = format!("{}  {} {} {} {} {}  {}", BANK_MARKER, bank.grain, bank.wool, bank.brick, bank.lumber, bank.ore, deck);
+        output.push('\n');
+        output.push_str(line.trim_end());
+
+        output.push('\n');
+        output.push_str(RESOURCE_HEADER);
+        for player in RESOURCE_ORDER.iter().filter(|player| game.state.players.contains(player)) {
+            let count = &game.state.resources[*player];
+            let line = format!("{}  {:<3}{:<3}{:<3}{:<3}{:<3}", char::from(*player),
+                               count.grain, count.wool, count.brick, count.lumber, count.ore);
+            output.push('\n');
+            output.push_str(line.trim_end());
+        }
+
         output
     }
 }
@@ -495,11 +977,8 @@ mod tests {
             Road { id: PathId(52), player: Player::Blue },
         ];
 
-        let state = State {
-            buildings,
-            roads,
-            robber: RobberId(7),
-            resources: PlayerResourceCount {
+        let state = State::new(Layout::Standard, Player::THREE.to_vec(), buildings, roads, RobberId(7),
+            PlayerResourceCount {
                 red: ResourceCount {
                     grain: 2,
                     wool: 3,
@@ -521,8 +1000,8 @@ mod tests {
                     lumber: 4,
                     ore: 5,
                 },
-            },
-        };
+                ..PlayerResourceCount::default()
+            });
 
         let game1 = Game { board, state };
 
@@ -534,8 +1013,9 @@ mod tests {
         assert_eq!(string1, string2);
     }
 
+    #[allow(clippy::zero_prefixed_literal)]
     fn get_board() -> Board {
-        let tiles = [
+        let tiles = vec![
             Tile { dice: 10, kind: TileKind::Ore },
             Tile { dice: 02, kind: TileKind::Wool },
             Tile { dice: 09, kind: TileKind::Lumber },
@@ -555,8 +1035,7 @@ mod tests {
             Tile { dice: 05, kind: TileKind::Brick },
             Tile { dice: 06, kind: TileKind::Grain },
             Tile { dice: 11, kind: TileKind::Wool }];
-        let board = Board::new(tiles);
-        board
+        Board::new(tiles)
     }
 
     #[test]
@@ -576,34 +1055,31 @@ mod tests {
         }
 
 
-        let state = State {
-            buildings,
-            roads,
-            robber: RobberId(8),
-            resources: PlayerResourceCount{
+        let state = State::new(Layout::Standard, Player::THREE.to_vec(), buildings, roads, RobberId(8),
+            PlayerResourceCount {
                 red: ResourceCount {
-                    grain: 10,
-                    wool: 10,
-                    brick: 10,
-                    lumber: 10,
-                    ore: 10,
+                    grain: 6,
+                    wool: 6,
+                    brick: 6,
+                    lumber: 6,
+                    ore: 6,
                 },
                 blue: ResourceCount {
-                    grain: 10,
-                    wool: 10,
-                    brick: 10,
-                    lumber: 10,
-                    ore: 10,
+                    grain: 6,
+                    wool: 6,
+                    brick: 6,
+                    lumber: 6,
+                    ore: 6,
                 },
                 white: ResourceCount {
-                    grain: 10,
-                    wool: 10,
-                    brick: 10,
-                    lumber: 10,
-                    ore: 10,
+                    grain: 6,
+                    wool: 6,
+                    brick: 6,
+                    lumber: 6,
+                    ore: 6,
                 },
-            },
-        };
+                ..PlayerResourceCount::default()
+            });
 
         let board = get_board();
         let game1 = Game { board, state };
@@ -616,4 +1092,266 @@ mod tests {
 
         assert_eq!(string1, string2);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_harbors() {
+        let board = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo
+P  R  B  W";
+        let game: Game = board.to_string().try_into().unwrap();
+        assert_eq!(game.board.harbors, STANDARD_HARBORS.to_vec());
+
+        let game: Game = format!("{}\nH  0-1:?  45-46:W", board).try_into().unwrap();
+        assert_eq!(game.board.harbors, vec![
+            Harbor { intersections: (IntersectionId(0), IntersectionId(1)), kind: HarborKind::Generic },
+            Harbor { intersections: (IntersectionId(45), IntersectionId(46)), kind: HarborKind::Resource(TileKind::Wool) },
+        ]);
+        let string: String = game.into();
+        assert!(string.contains("\nH  0-1:?  45-46:W\n"));
+
+        let invalid: Result<Game, _> = format!("{}\nH  0-1:N", board).try_into();
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_round_trip_turn_and_development_cards() {
+        let board = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo R oo R RS R oo . oo B BS . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . WS . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+        let mut game: Game = board.to_string().try_into().unwrap();
+        game.state.turn = Turn {
+            player: Player::Blue,
+            phase: Phase::Trade {
+                offer: TradeOffer {
+                    from: Player::Blue,
+                    to: None,
+                    give: ResourceCount::single(TileKind::Brick, 2),
+                    get: ResourceCount::single(TileKind::Ore, 1),
+                },
+                waiting: vec![Player::White, Player::Red],
+            },
+            number: 12,
+            rolled: true,
+            development_played: true,
+        };
+        let bought = game.state.bank.development.pop().unwrap();
+        game.state.development_cards.push(HeldCard { player: Player::Red, card: bought, bought_turn: 11 });
+        game.state.development_cards.push(HeldCard { player: Player::Red, card: DevelopmentCard::VictoryPoint, bought_turn: 3 });
+        game.state.played_knights = vec![Player::Blue, Player::Blue];
+        game.state.bank.resources.ore -= 1;
+
+        let string: String = game.clone().into();
+        assert!(string.contains("\nT  B  trade:B:-:0,0,2,0,0:0,0,0,0,1:WR  12  1  1\n"));
+        assert!(string.contains("\nD  B  2\n"));
+        let parsed: Game = string.clone().try_into().unwrap();
+        assert_eq!(parsed.state.turn, game.state.turn);
+        assert_eq!(parsed.state.development_cards, game.state.development_cards);
+        assert_eq!(parsed.state.played_knights, game.state.played_knights);
+        assert_eq!(parsed.state.bank, game.state.bank);
+        let again: String = parsed.into();
+        assert_eq!(string, again);
+
+        let phases = [
+            Phase::Setup { step: 3, settlement: Some(IntersectionId(10)) },
+            Phase::Setup { step: 0, settlement: None },
+            Phase::Roll,
+            Phase::Main,
+            Phase::Discard { players: vec![Player::Red] },
+            Phase::MoveRobber,
+            Phase::RoadBuilding { roads: 1 },
+            Phase::SpecialBuilding { players: vec![Player::White, Player::Red] },
+            Phase::GameOver,
+        ];
+        for phase in phases.iter() {
+            assert_eq!(parse_phase(&phase_token(phase), Layout::Standard.bank_resources()).as_ref(), Ok(phase));
+        }
+
+        let game: Game = format!("{}\nT  W  main  4  1  0\nD  W  0  K2", board).try_into().unwrap();
+        assert_eq!(game.state.turn, Turn { player: Player::White, phase: Phase::Main, number: 4, rolled: true, development_played: false });
+        assert_eq!(game.state.development_cards, vec![HeldCard { player: Player::White, card: DevelopmentCard::Knight, bought_turn: 2 }]);
+        assert_eq!(game.state.bank.development.len(), Layout::Standard.development_deck().len() - 1);
+
+        let invalid: Result<Game, _> = format!("{}\nT  O  main  4  1  0", board).try_into();
+        assert_eq!(invalid.err(), Some("The turn player is not in the game"));
+        let invalid: Result<Game, _> = format!("{}\nT  W  dance  4  1  0", board).try_into();
+        assert_eq!(invalid.err(), Some("Invalid phase"));
+    }
+
+    #[test]
+    fn test_parse_four_players() {
+        let board = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . OS O oo . oo . oo . oo . oo . oo . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+        let game: Game = format!("{}\nP  R  B  W  O", board).try_into().unwrap();
+        assert_eq!(game.state.players, Player::FOUR.to_vec());
+        assert_eq!(game.state.buildings[0].player, Player::Orange);
+        assert_eq!(game.state.roads[0].player, Player::Orange);
+        let invalid: Result<Game, _> = board.to_string().try_into();
+        assert_eq!(invalid.err(), Some("A player in the game is not seated"));
+
+        let three_players = board.replace("OS O", "oo .");
+        let game: Game = format!("{}\nP  R  B  W  O\n   G  W  B  L  O\nO  1  0  0  0  2", three_players).try_into().unwrap();
+        assert_eq!(game.state.players, Player::FOUR.to_vec());
+        assert_eq!(game.state.resources.orange, ResourceCount { grain: 1, wool: 0, brick: 0, lumber: 0, ore: 2 });
+        let string: String = game.into();
+        assert!(string.ends_with("\nO  1  0  0  0  2"));
+
+        let game: Game = three_players.to_string().try_into().unwrap();
+        assert_eq!(game.state.players, Player::THREE.to_vec());
+        let game: Game = format!("{}\nP  R  B  W", three_players).try_into().unwrap();
+        assert_eq!(game.state.players, Player::THREE.to_vec());
+        let string: String = game.into();
+        assert!(!string.contains("\nO "));
+    }
+
+    #[test]
+    fn test_round_trip_seat_order() {
+        let board = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo R oo R RS R oo . oo B BS . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . WS . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+        let game: Game = board.to_string().try_into().unwrap();
+        assert_eq!(game.state.players, Player::THREE.to_vec());
+
+        let game: Game = format!("{}\nP  W  N  B  R", board).try_into().unwrap();
+        let seats = vec![Player::White, Player::Brown, Player::Blue, Player::Red];
+        assert_eq!(game.state.players, seats);
+        assert_eq!(game.state.turn.player, Player::White);
+        let string: String = game.into();
+        assert!(string.contains("\nP  W  N  B  R\n"));
+        let parsed: Game = string.clone().try_into().unwrap();
+        assert_eq!(parsed.state.players, seats);
+        let again: String = parsed.into();
+        assert_eq!(string, again);
+
+        let two_players = board.replace("WS", "oo");
+        let game: Game = two_players.clone().try_into().unwrap();
+        assert_eq!(game.state.players, Player::THREE.to_vec());
+        let invalid: Result<Game, _> = format!("{}\nP  R  B", two_players).try_into();
+        assert_eq!(invalid.err(), Some("A game needs at least three players"));
+
+        let invalid: Result<Game, _> = format!("{}\nP  W  B", board).try_into();
+        assert_eq!(invalid.err(), Some("A player in the game is not seated"));
+        let invalid: Result<Game, _> = format!("{}\nP  W  B  R  W", board).try_into();
+        assert_eq!(invalid.err(), Some("A player is seated twice"));
+    }
+
+    #[test]
+    fn test_parse_malformed_boards() {
+        let board = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo R oo R RS R oo . oo B BS . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . WS . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+        assert!(Game::try_from(board.to_string()).is_ok());
+
+        let truncated: String = board.lines().take(7).collect::<Vec<_>>().join("\n");
+        assert_eq!(Game::try_from(truncated).err(), Some("The board is missing rows"));
+
+        let short = board.replace("     .   08L   .   03O   .   04G   .   05W   .", "     .   08L   .   03O   .");
+        assert_eq!(Game::try_from(short).err(), Some("A row of the board is too short"));
+
+        let no_robber = board.replace("09G!", "09G ");
+        assert_eq!(Game::try_from(no_robber).err(), Some("The board has no robber"));
+
+        let bad_dice = board.replace("11L", "1xL");
+        assert_eq!(Game::try_from(bad_dice).err(), Some("Invalid tile dice number"));
+
+        let negative = format!("{}\n   G  W  B  L  O\nR  -128  0  0  0  0", board);
+        assert_eq!(Game::try_from(negative).err(), Some("Invalid resource count"));
+        let hoarded = format!("{}\n   G  W  B  L  O\nR  20  0  0  0  0", board);
+        assert_eq!(Game::try_from(hoarded).err(), Some("Invalid resource count"));
+        let shared = format!("{}\n   G  W  B  L  O\nW  19  0  0  0  0\nR  19  0  0  0  0\nB  19  0  0  0  0", board);
+        assert_eq!(Game::try_from(shared).err(), Some("More resource cards are held than the bank has"));
+        let all = format!("{}\n   G  W  B  L  O\nW  7  0  0  0  0\nR  6  0  0  0  0\nB  6  0  0  0  0", board);
+        assert_eq!(Game::try_from(all).unwrap().state.bank.resources.grain, 0);
+        let bank = format!("{}\nX  19  19  19  19  -1", board);
+        assert_eq!(Game::try_from(bank).err(), Some("Invalid resource count"));
+
+        assert!(Game::try_from(String::new()).is_err());
+        assert!(Game::try_from("\n          oo".to_string()).is_err());
+    }
+
+    fn extension_board() -> String {
+        let kinds = ['G', 'W', 'B', 'L', 'O'];
+        let mut board = EXTENSION_TEMPLATE.replace("BB", "oo").replace('*', ".").replacen("TTTT", "00N!", 1);
+        for i in 1..EXTENSION_TILES {
+            board = board.replacen("TTTT", &format!("{:02}{} ", 2 + i % 11, kinds[i % 5]), 1);
+        }
+        board
+    }
+
+    #[test]
+    fn test_parse_extension() {
+        let mut game: Game = format!("{}\nP  R  B  W", extension_board()).try_into().unwrap();
+        assert_eq!(game.board.layout, Layout::Extension);
+        assert_eq!(game.board.tiles.len(), EXTENSION_TILES);
+        assert_eq!(game.board.harbors, EXTENSION_HARBORS.to_vec());
+        assert_eq!(game.state.robber, RobberId(0));
+        assert_eq!(game.state.players, Player::THREE.to_vec());
+        assert_eq!(game.state.bank.resources.ore, 24);
+        assert_eq!(game.state.bank.development.len(), 34);
+
+        game.state.players = Player::ALL.to_vec();
+        game.state.buildings.push(Building { intersection_id: IntersectionId(79), kind: BuildingKind::Settlement, player: Player::Green });
+        game.state.roads.push(Road { id: PathId(EXTENSION_PATHS - 1), player: Player::Brown });
+        game.state.resources.brown = ResourceCount::single(TileKind::Ore, 3);
+        let string: String = game.into();
+        assert!(string.contains("GS"));
+        assert!(string.ends_with("\nN  0  0  0  0  3"));
+
+        let game: Game = string.clone().try_into().unwrap();
+        assert_eq!(game.state.players, Player::ALL.to_vec());
+        assert_eq!(game.state.buildings[0].player, Player::Green);
+        assert_eq!((game.state.roads[0].id, game.state.roads[0].player), (PathId(EXTENSION_PATHS - 1), Player::Brown));
+        let again: String = game.into();
+        assert_eq!(string, again);
+
+        let table: String = Player::ALL.iter().map(|player| format!("\n{}  24  0  0  0  0", char::from(*player))).collect();
+        let hoarded = format!("{}\nP  R  B  W  O  G  N\n   G  W  B  L  O{}", extension_board(), table);
+        assert_eq!(Game::try_from(hoarded).err(), Some("More resource cards are held than the bank has"));
+    }
+}
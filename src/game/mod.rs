@@ -1,5 +1,19 @@
 pub mod board;
 pub mod encoding;
-mod resources;
+pub mod resources;
+pub mod actions;
+pub mod dice;
+pub mod development;
+pub mod pieces;
+pub mod production;
+pub mod scoring;
+pub mod topology;
+pub mod trade;
+pub mod turn;
+#[cfg(test)]
+pub(crate) mod test_support;
 
 pub use self::board::*;
+pub use self::actions::*;
+pub use self::scoring::*;
+pub use self::turn::*;
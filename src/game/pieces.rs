@@ -0,0 +1,90 @@
+use crate::game::board::*;
+
+/// The number of roads each player has.
+pub const ROAD_PIECES: u8 = 15;
+
+/// The number of settlements each player has.
+pub const SETTLEMENT_PIECES: u8 = 5;
+
+/// The number of cities each player has.
+pub const CITY_PIECES: u8 = 4;
+
+/// A count of the pieces of a player that are not on the board yet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Pieces {
+    pub roads: u8,
+    pub settlements: u8,
+    pub cities: u8,
+}
+
+impl Game {
+    /// Returns the pieces a player can still place.
+    ///
+    /// A settlement upgraded to a city goes back to the supply, so it can be placed again.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let pieces = game.remaining_pieces(Player::Red);
+    /// println!("Red has {} roads left", pieces.roads);
+    /// ```
+    pub fn remaining_pieces(&self, player: Player) -> Pieces {
+        let placed = |kind: BuildingKind| self.state.buildings.iter()
+            .filter(|building| building.player == player && building.kind == kind)
+            .count();
+        let roads = self.state.roads.iter().filter(|road| road.player == player).count();
+        Pieces {
+            roads: (ROAD_PIECES as usize).saturating_sub(roads) as u8,
+            settlements: (SETTLEMENT_PIECES as usize).saturating_sub(placed(BuildingKind::Settlement)) as u8,
+            cities: (CITY_PIECES as usize).saturating_sub(placed(BuildingKind::City)) as u8,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::crowded_game;
+    use crate::game::{Action, Phase, RuleError};
+    use crate::game::resources::ResourceCount;
+
+    fn get_game() -> Game {
+        crowded_game(Some("   G  W  B  L  O\nW  9  9  9  9  9\nR  9  9  9  9  9\nB  0  0  0  0  0"))
+    }
+
+    #[test]
+    fn test_remaining_pieces() {
+        let game = get_game();
+        assert_eq!(game.remaining_pieces(Player::Red), Pieces { roads: 13, settlements: 2, cities: 3 });
+        assert_eq!(game.remaining_pieces(Player::White), Pieces { roads: 7, settlements: 3, cities: 4 });
+    }
+
+    #[test]
+    fn test_piece_limits() {
+        let mut game = get_game();
+        game.state.turn.phase = Phase::Main;
+        game.state.turn.rolled = true;
+        game.state.victory_target = 20;
+        for intersection in [0, 47] {
+            game.state.buildings.push(Building { intersection_id: IntersectionId(intersection), kind: BuildingKind::Settlement, player: Player::Red });
+        }
+        assert_eq!(game.remaining_pieces(Player::Red).settlements, 0);
+        let road = Action::BuildRoad { player: Player::Red, path: PathId(20) };
+        assert!(game.apply(road).is_ok());
+        let settlement = Action::BuildSettlement { player: Player::Red, intersection: IntersectionId(21) };
+        assert_eq!(game.apply(settlement), Err(RuleError::NoPiecesLeft));
+        assert!(!game.possible_actions(Player::Red).contains(&settlement));
+
+        // Upgrading a settlement returns it to the supply.
+        assert!(game.apply(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }).is_ok());
+        assert!(game.possible_actions(Player::Red).contains(&settlement));
+        assert!(game.apply(settlement).is_ok());
+
+        game.state.resources.red = ResourceCount { grain: 9, wool: 9, brick: 9, lumber: 9, ore: 9 };
+        for intersection in [0, 47, 21] {
+            let city = Action::BuildCity { player: Player::Red, intersection: IntersectionId(intersection) };
+            let expected = if intersection == 21 { Err(RuleError::NoPiecesLeft) } else { Ok(()) };
+            assert_eq!(game.apply(city).map(|_| ()), expected);
+        }
+    }
+}
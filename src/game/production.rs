@@ -0,0 +1,156 @@
+use crate::game::board::*;
+use crate::game::resources::{PlayerResourceCount, ResourceCount, RESOURCES};
+
+impl Game {
+    /// Pays out the resources produced by a dice roll.
+    ///
+    /// Every tile showing `roll` pays its `TileKind` to the buildings on its corners:
+    /// one card per settlement and two per city. The tile under the robber and the
+    /// desert produce nothing.
+    ///
+    /// The cards are taken from the bank following the official shortage rule: if the bank
+    /// cannot pay everybody a resource, nobody receives it, unless only one player is owed
+    /// that resource, who then receives what is left.
+    ///
+    /// # Arguments
+    /// - `roll`: The sum of the two dice.
+    ///
+    /// # Returns
+    /// A `PlayerResourceCount` with the cards each player received.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let produced = game.produce(8);
+    /// println!("Red received {:?}", produced[Player::Red]);
+    /// ```
+    pub fn produce(&mut self, roll: u8) -> PlayerResourceCount {
+        let mut owed = PlayerResourceCount::default();
+        for building in &self.state.buildings {
+            let amount = match building.kind {
+                BuildingKind::Settlement => 1,
+                BuildingKind::City => 2,
+            };
+            for TileId(id) in self.board.tiles_of(building.intersection_id) {
+                let tile = &self.board.tiles[*id];
+                if tile.dice != roll || tile.kind == TileKind::Nothing || RobberId(*id) == self.state.robber {
+                    continue;
+                }
+                owed[building.player] = owed[building.player] + ResourceCount::single(tile.kind, amount);
+            }
+        }
+
+        let mut produced = PlayerResourceCount::default();
+        for kind in RESOURCES.iter() {
+            let supply = self.state.bank.resources[*kind];
+            let total: i8 = self.state.players.iter().map(|player| owed[*player][*kind]).sum();
+            let owed_players: Vec<&Player> = self.state.players.iter().filter(|player| owed[**player][*kind] > 0).collect();
+            for player in owed_players.iter() {
+                let amount = if total <= supply {
+                    owed[**player][*kind]
+                } else if owed_players.len() == 1 {
+                    supply
+                } else {
+                    0
+                };
+                produced[**player] = produced[**player] + ResourceCount::single(*kind, amount);
+            }
+        }
+
+        let paid = produced.sum();
+        self.state.bank.resources = self.state.bank.resources - paid;
+        for player in self.state.players.clone().iter() {
+            self.state.resources[*player] = self.state.resources[*player] + produced[*player];
+        }
+        produced
+    }
+
+    /// Gives `player` the starting resources of their second setup settlement: one card from
+    /// the bank for every producing tile around `intersection`, as far as the bank holds them.
+    ///
+    /// # Returns
+    /// The cards the player received.
+    pub fn grant_starting_resources(&mut self, player: Player, intersection: IntersectionId) -> ResourceCount {
+        let mut granted = ResourceCount::default();
+        for TileId(id) in self.board.tiles_of(intersection) {
+            let kind = self.board.tiles[*id].kind;
+            if kind != TileKind::Nothing && self.state.bank.resources[kind] > granted[kind] {
+                granted = granted + ResourceCount::single(kind, 1);
+            }
+        }
+        self.state.bank.resources = self.state.bank.resources - granted;
+        self.state.resources[player] = self.state.resources[player] + granted;
+        granted
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::crowded_game;
+
+    fn get_game() -> Game {
+        crowded_game(None)
+    }
+
+    #[test]
+    fn test_produce_settlements_and_cities() {
+        let mut game = get_game();
+        let produced = game.produce(8);
+        assert_eq!(produced.red, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 3, ore: 0 });
+        assert_eq!(produced.white, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 0, ore: 1 });
+        assert_eq!(produced.blue, ResourceCount::default());
+        assert_eq!(game.state.resources, produced);
+
+        let produced = game.produce(10);
+        assert_eq!(produced.red, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 0, ore: 1 });
+        assert_eq!(produced.blue, ResourceCount { grain: 0, wool: 0, brick: 1, lumber: 0, ore: 0 });
+        assert_eq!(game.state.resources.red, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 3, ore: 1 });
+    }
+
+    #[test]
+    fn test_produce_skips_robber() {
+        let mut game = get_game();
+        let produced = game.produce(9);
+        assert_eq!(produced.red, ResourceCount::default());
+        assert_eq!(produced.blue, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 1, ore: 0 });
+
+        game.state.robber = RobberId(2);
+        let produced = game.produce(9);
+        assert_eq!(produced.red, ResourceCount { grain: 1, wool: 0, brick: 0, lumber: 0, ore: 0 });
+        assert_eq!(produced.blue, ResourceCount::default());
+    }
+
+    #[test]
+    fn test_produce_bank_shortage() {
+        let mut game = get_game();
+        game.state.bank.resources.ore = 1;
+        game.state.bank.resources.lumber = 2;
+
+        // Red is owed 3 lumber and is the only player owed lumber, so takes the last 2.
+        // Only white is owed ore, so the last ore goes to white.
+        let produced = game.produce(8);
+        assert_eq!(produced.red, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 2, ore: 0 });
+        assert_eq!(produced.white, ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 0, ore: 1 });
+        assert_eq!(game.state.bank.resources.lumber, 0);
+        assert_eq!(game.state.bank.resources.ore, 0);
+
+        // Red and white are both owed wool but the bank only holds one: nobody receives it.
+        game.state.bank.resources.wool = 1;
+        let produced = game.produce(5);
+        assert_eq!(produced.red, ResourceCount { grain: 0, wool: 0, brick: 2, lumber: 0, ore: 0 });
+        assert_eq!(produced.white, ResourceCount::default());
+        assert_eq!(game.state.bank.resources.wool, 1);
+    }
+
+    #[test]
+    fn test_grant_starting_resources() {
+        let mut game = get_game();
+        game.state.bank.resources.brick = 0;
+        // Intersection 10 touches 10O, 02W and 06B, and the bank has run out of brick.
+        let granted = game.grant_starting_resources(Player::Blue, IntersectionId(10));
+        assert_eq!(granted, ResourceCount { grain: 0, wool: 1, brick: 0, lumber: 0, ore: 1 });
+        assert_eq!(game.state.resources.blue, granted);
+        assert_eq!(game.state.bank.resources.wool, 18);
+    }
+}
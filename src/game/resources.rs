@@ -1,8 +1,9 @@
 use std::collections::HashSet;
-use std::ops::{Add, Index, Sub};
-use crate::game::{Player, TileKind};
+use std::ops::{Add, Index, IndexMut, Sub};
+use crate::game::{Layout, Player, TileKind};
+use crate::game::development::DevelopmentCard;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Default)]
 pub struct ResourceCount {
     pub grain: i8,
     pub wool: i8,
@@ -12,10 +13,11 @@ pub struct ResourceCount {
 }
 
 #[derive(Eq, Hash, PartialEq, Debug)]
-enum Buys {
+pub enum Buys {
     Road,
     Settlement,
-    City
+    City,
+    DevelopmentCard,
 }
 
 impl Index<TileKind> for ResourceCount {
@@ -31,11 +33,14 @@ impl Index<TileKind> for ResourceCount {
         }
     }
 }
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Default)]
 pub struct PlayerResourceCount {
     pub red: ResourceCount,
     pub blue: ResourceCount,
     pub white: ResourceCount,
+    pub orange: ResourceCount,
+    pub green: ResourceCount,
+    pub brown: ResourceCount,
 }
 
 impl Index<Player> for PlayerResourceCount {
@@ -45,13 +50,45 @@ impl Index<Player> for PlayerResourceCount {
         match index {
             Player::Red => &self.red,
             Player::Blue => &self.blue,
-            Player::White => &self.white
+            Player::White => &self.white,
+            Player::Orange => &self.orange,
+            Player::Green => &self.green,
+            Player::Brown => &self.brown,
         }
     }
 }
 
+impl PlayerResourceCount {
+    /// Returns the sum of the resources held by all players.
+    pub fn sum(&self) -> ResourceCount {
+        Player::ALL.iter().fold(ResourceCount::default(), |sum, player| sum + self[*player])
+    }
+}
+
+impl IndexMut<Player> for PlayerResourceCount {
+    fn index_mut(&mut self, index: Player) -> &mut Self::Output {
+        match index {
+            Player::Red => &mut self.red,
+            Player::Blue => &mut self.blue,
+            Player::White => &mut self.white,
+            Player::Orange => &mut self.orange,
+            Player::Green => &mut self.green,
+            Player::Brown => &mut self.brown,
+        }
+    }
+}
+
+
+/// All tile kinds that produce a resource, in the order of the resource table.
+pub const RESOURCES: [TileKind; 5] = [TileKind::Grain, TileKind::Wool, TileKind::Brick, TileKind::Lumber, TileKind::Ore];
+
+/// The number of cards of each resource in the bank at the start of the game.
+pub const BANK_RESOURCES: i8 = 19;
 
-const ROAD_COST: ResourceCount = ResourceCount{
+/// The number of cards of each resource in the bank of the 5-6 player extension.
+pub const EXTENSION_BANK_RESOURCES: i8 = 24;
+
+pub const ROAD_COST: ResourceCount = ResourceCount{
     grain: 0,
     wool: 0,
     brick: 1,
@@ -59,7 +96,7 @@ const ROAD_COST: ResourceCount = ResourceCount{
     ore: 0,
 };
 
-const SETTLEMENT_COST: ResourceCount = ResourceCount{
+pub const SETTLEMENT_COST: ResourceCount = ResourceCount{
     grain: 1,
     wool: 1,
     brick: 1,
@@ -67,7 +104,7 @@ const SETTLEMENT_COST: ResourceCount = ResourceCount{
     ore: 0,
 };
 
-const CITY_COST: ResourceCount = ResourceCount{
+pub const CITY_COST: ResourceCount = ResourceCount{
     grain: 3,
     wool: 0,
     brick: 0,
@@ -75,27 +112,142 @@ const CITY_COST: ResourceCount = ResourceCount{
     ore: 2,
 };
 
+pub const DEVELOPMENT_COST: ResourceCount = ResourceCount{
+    grain: 1,
+    wool: 1,
+    brick: 0,
+    lumber: 0,
+    ore: 1,
+};
+
+/// Represents the supply of the bank: the resource cards and the development cards
+/// that are not held by any player. Cards are bought from the end of `development`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bank {
+    pub resources: ResourceCount,
+    pub development: Vec<DevelopmentCard>,
+}
+
+impl Bank {
+    /// Creates a bank holding the resource cards not held by the players and a full,
+    /// unshuffled development deck.
+    ///
+    /// # Arguments
+    /// - `layout`: The board layout, which decides the size of the bank and the deck.
+    /// - `in_hands`: The resource cards held by all players together.
+    ///
+    /// # Panics
+    /// In debug builds, panics if the players hold more cards of a resource than the bank starts with.
+    pub fn without(layout: Layout, in_hands: ResourceCount) -> Bank {
+        let supply = layout.bank_resources();
+        debug_assert!(RESOURCES.iter().all(|kind| in_hands[*kind] <= supply),
+                      "The players hold more resource cards than the bank starts with");
+        let full = ResourceCount {
+            grain: supply,
+            wool: supply,
+            brick: supply,
+            lumber: supply,
+            ore: supply,
+        };
+        let remaining = full - in_hands;
+        Bank {
+            resources: ResourceCount {
+                grain: remaining.grain.max(0),
+                wool: remaining.wool.max(0),
+                brick: remaining.brick.max(0),
+                lumber: remaining.lumber.max(0),
+                ore: remaining.ore.max(0),
+            },
+            development: layout.development_deck(),
+        }
+    }
+}
+
+impl Default for Bank {
+    /// Creates a full bank of the standard board, as at the start of the game.
+    fn default() -> Self {
+        Bank::without(Layout::Standard, ResourceCount::default())
+    }
+}
+
 impl ResourceCount {
+    /// Creates a count holding `amount` cards of the resource produced by `kind`.
+    pub fn single(kind: TileKind, amount: i8) -> ResourceCount {
+        let mut count = ResourceCount::default();
+        match kind {
+            TileKind::Grain => count.grain = amount,
+            TileKind::Wool => count.wool = amount,
+            TileKind::Brick => count.brick = amount,
+            TileKind::Lumber => count.lumber = amount,
+            TileKind::Ore => count.ore = amount,
+            TileKind::Nothing => {}
+        }
+        count
+    }
+
     pub fn is_positive(&self) -> bool {
         self.grain >= 0 && self.wool >= 0 && self.brick >= 0 && self.lumber >= 0 && self.ore >= 0
     }
 
+    /// Returns the total number of resource cards.
+    pub fn total(&self) -> i32 {
+        self.grain as i32 + self.wool as i32 + self.brick as i32 + self.lumber as i32 + self.ore as i32
+    }
+
     fn possible_buys_dfs(&self, resource_count: ResourceCount, buys: &mut HashSet<Buys>) {
-        let zip = [ROAD_COST, SETTLEMENT_COST, CITY_COST].iter().zip([Buys::Road, Buys::Settlement, Buys::City]);
+        let zip = [ROAD_COST, SETTLEMENT_COST, CITY_COST, DEVELOPMENT_COST].iter()
+            .zip([Buys::Road, Buys::Settlement, Buys::City, Buys::DevelopmentCard]);
         for (cost, buy) in zip {
-            let sub_count = resource_count.clone() - cost.clone();
+            let sub_count = resource_count - *cost;
             if sub_count.is_positive() {
                 buys.insert(buy);
                 self.possible_buys_dfs(sub_count, buys);
             }
         }
     }
-    fn possible_buys(&self) -> HashSet<Buys> {
+    pub fn possible_buys(&self) -> HashSet<Buys> {
         let mut buys: HashSet<Buys> = HashSet::new();
-        self.possible_buys_dfs(self.clone(), &mut buys);
+        self.possible_buys_dfs(*self, &mut buys);
         buys
     }
 
+    /// Tells whether `cost` can be paid after trading surplus cards with the bank.
+    ///
+    /// # Arguments
+    /// - `cost`: The cards to pay.
+    /// - `ratios`: How many cards of each resource are given to the bank for one card.
+    pub fn can_afford_with_trades(&self, cost: ResourceCount, ratios: ResourceCount) -> bool {
+        let remaining = *self - cost;
+        let mut missing = 0;
+        let mut tradable = 0;
+        for kind in RESOURCES.iter() {
+            if remaining[*kind] < 0 {
+                missing -= remaining[*kind] as i32;
+            } else {
+                tradable += (remaining[*kind] / ratios[*kind]) as i32;
+            }
+        }
+        tradable >= missing
+    }
+
+    /// Returns what can be bought with these cards, trading surplus cards with the bank at `ratios`.
+    ///
+    /// The bank is assumed to hold the cards asked for.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let hand = ResourceCount { grain: 0, wool: 0, brick: 5, lumber: 0, ore: 0 };
+    /// let ratios = ResourceCount { grain: 4, wool: 4, brick: 4, lumber: 4, ore: 4 };
+    /// assert!(hand.possible_buys_with_trades(ratios).contains(&Buys::Road));
+    /// ```
+    pub fn possible_buys_with_trades(&self, ratios: ResourceCount) -> HashSet<Buys> {
+        [ROAD_COST, SETTLEMENT_COST, CITY_COST, DEVELOPMENT_COST].iter()
+            .zip([Buys::Road, Buys::Settlement, Buys::City, Buys::DevelopmentCard])
+            .filter(|(cost, _)| self.can_afford_with_trades(**cost, ratios))
+            .map(|(_, buy)| buy)
+            .collect()
+    }
+
 }
 
 impl Sub<Self> for ResourceCount {
@@ -132,7 +284,8 @@ mod tests {
     use std::collections::HashSet;
     use std::convert::TryInto;
     use crate::game::Game;
-    use crate::game::resources::{Buys, PlayerResourceCount, ResourceCount, SETTLEMENT_COST};
+    use crate::game::test_support::crowded_game;
+    use crate::game::resources::{Buys, PlayerResourceCount, ResourceCount, CITY_COST, SETTLEMENT_COST};
 
     #[test]
     fn test_parse_resources() {
@@ -151,7 +304,7 @@ oo . oo . RS R oo . oo . oo . oo . oo . WS . oo . oo
    G  W  B  L  O
 W  1  2  3  4  5  
 R  6  7  8  9  10 
-B  11 12 13 14 15"
+B  11 10 8  6  4"
             .to_string()
             .try_into()
             .unwrap();
@@ -165,10 +318,10 @@ B  11 12 13 14 15"
             },
             blue: ResourceCount {
                 grain: 11,
-                wool: 12,
-                brick: 13,
-                lumber: 14,
-                ore: 15,
+                wool: 10,
+                brick: 8,
+                lumber: 6,
+                ore: 4,
             },
             white: ResourceCount {
                 grain: 1,
@@ -177,6 +330,7 @@ B  11 12 13 14 15"
                 lumber: 4,
                 ore: 5,
             },
+            ..PlayerResourceCount::default()
         };
         assert_eq!(s, game.state.resources);
     }
@@ -184,25 +338,7 @@ B  11 12 13 14 15"
 
     #[test]
     fn test_calculate_resources() {
-        let game: Game = "
-          oo . oo . oo . oo . oo W oo W oo
-          .   10O   .   02W   .   09L   W
-     oo . oo . oo . RS R oo . oo B BS W oo . oo
-     .   12G   .   06B   .   04W   W   10B   .
-oo . oo . oo W WS . oo . oo . oo . oo W oo . oo . oo
-.   09G!  .   11L   .   00N   .   03L   W   08O   .
-oo . oo . RS R oo . oo . oo . oo . oo . WS . oo . oo
-     .   08L   .   03O   .   04G   B   05W   .
-     oo . oo . RS B oo . oo . oo . RS . oo . oo
-          .   05B   .   06G   .   11W   .
-          oo . oo . oo . oo . oo . oo . oo
-   G  W  B  L  O
-W  1  1  1  1  1
-R  6  7  8  9  10
-B  11 12 13 14 15"
-            .to_string()
-            .try_into()
-            .unwrap();
+        let game = crowded_game(Some("   G  W  B  L  O\nW  1  1  1  1  1\nR  6  7  8  9  10\nB  11 11 10 9  8"));
 
         let s = ResourceCount { grain: 0, wool: 0, brick: 0, lumber: 0, ore: 1 };
         let x = game.state.resources.white - SETTLEMENT_COST;
@@ -211,31 +347,27 @@ B  11 12 13 14 15"
 
     #[test]
     fn test_possible_buys() {
-        let game: Game = "
-          oo . oo . oo . oo . oo W oo W oo
-          .   10O   .   02W   .   09L   W
-     oo . oo . oo . RS R oo . oo B BS W oo . oo
-     .   12G   .   06B   .   04W   W   10B   .
-oo . oo . oo W WS . oo . oo . oo . oo W oo . oo . oo
-.   09G!  .   11L   .   00N   .   03L   W   08O   .
-oo . oo . RS R oo . oo . oo . oo . oo . WS . oo . oo
-     .   08L   .   03O   .   04G   B   05W   .
-     oo . oo . RS B oo . oo . oo . RS . oo . oo
-          .   05B   .   06G   .   11W   .
-          oo . oo . oo . oo . oo . oo . oo
-   G  W  B  L  O
-W  1  1  1  1  1
-R  6  7  8  9  10
-B  11 12 13 14 15"
-            .to_string()
-            .try_into()
-            .unwrap();
+        let game = crowded_game(Some("   G  W  B  L  O\nW  1  1  1  1  1\nR  6  7  8  9  10\nB  11 11 10 9  8"));
 
         let buys = game.state.resources.white.possible_buys();
 
-        let a: HashSet<Buys>  = vec![Buys::Road, Buys::Settlement].into_iter().collect();
+        let a: HashSet<Buys>  = vec![Buys::Road, Buys::Settlement, Buys::DevelopmentCard].into_iter().collect();
 
         assert_eq!(a, buys);
     }
+
+    #[test]
+    fn test_possible_buys_with_trades() {
+        let ratios = ResourceCount { grain: 4, wool: 4, brick: 2, lumber: 4, ore: 4 };
+        let hand = ResourceCount { grain: 0, wool: 0, brick: 10, lumber: 0, ore: 0 };
+        assert!(hand.possible_buys().is_empty());
+        let buys: HashSet<Buys> = vec![Buys::Road, Buys::Settlement, Buys::City, Buys::DevelopmentCard].into_iter().collect();
+        assert_eq!(hand.possible_buys_with_trades(ratios), buys);
+
+        let hand = ResourceCount { grain: 1, wool: 0, brick: 0, lumber: 0, ore: 5 };
+        assert!(!hand.can_afford_with_trades(CITY_COST, ratios));
+        let hand = ResourceCount { grain: 1, wool: 0, brick: 0, lumber: 0, ore: 10 };
+        assert!(hand.can_afford_with_trades(CITY_COST, ratios));
+    }
 }
 
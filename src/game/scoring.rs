@@ -0,0 +1,272 @@
+use crate::game::board::*;
+use crate::game::development::DevelopmentCard;
+
+/// The number of victory points needed to win a standard game.
+pub const VICTORY_TARGET: u8 = 10;
+
+/// The minimum length of a road to claim the Longest Road card.
+pub const LONGEST_ROAD_MINIMUM: usize = 5;
+
+/// The minimum number of knights played to claim the Largest Army card.
+pub const LARGEST_ARMY_MINIMUM: usize = 3;
+
+/// The victory points of a player, broken down by where they come from.
+///
+/// - `settlements`: One point per settlement.
+/// - `cities`: Two points per city.
+/// - `longest_road`: Two points for the Longest Road card.
+/// - `largest_army`: Two points for the Largest Army card.
+/// - `development_cards`: One point per victory point development card.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct VictoryPoints {
+    pub settlements: u8,
+    pub cities: u8,
+    pub longest_road: u8,
+    pub largest_army: u8,
+    pub development_cards: u8,
+}
+
+impl VictoryPoints {
+    /// Returns the total number of victory points.
+    pub fn total(&self) -> u8 {
+        self.settlements + self.cities + self.longest_road + self.largest_army + self.development_cards
+    }
+
+    /// Returns the victory points the other players can see, without the development cards
+    /// that stay hidden in the player's hand until the game is won.
+    pub fn public(&self) -> u8 {
+        self.total() - self.development_cards
+    }
+}
+
+impl Game {
+    /// Counts the victory points of a player.
+    ///
+    /// # Arguments
+    /// - `player`: The player whose points are counted.
+    ///
+    /// # Returns
+    /// A `VictoryPoints` breakdown; use `total` for the score.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let points = game.victory_points(Player::Red);
+    /// println!("Red has {} points ({} from cities)", points.total(), points.cities);
+    /// ```
+    pub fn victory_points(&self, player: Player) -> VictoryPoints {
+        let mut points = VictoryPoints::default();
+        for building in self.state.buildings.iter().filter(|building| building.player == player) {
+            match building.kind {
+                BuildingKind::Settlement => points.settlements += 1,
+                BuildingKind::City => points.cities += 2,
+            }
+        }
+        if self.state.longest_road_holder == Some(player) {
+            points.longest_road = 2;
+        }
+        if self.state.largest_army_holder == Some(player) {
+            points.largest_army = 2;
+        }
+        points.development_cards = self.state.development_cards.iter()
+            .filter(|held| held.player == player && held.card == DevelopmentCard::VictoryPoint)
+            .count() as u8;
+        points
+    }
+
+    /// Returns the winner of the game, if any.
+    ///
+    /// A player wins by reaching `State.victory_target` points during their own turn,
+    /// so only the player whose turn it is can be the winner.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if let Some(player) = game.winner() {
+    ///     println!("{:?} wins", player);
+    /// }
+    /// ```
+    pub fn winner(&self) -> Option<Player> {
+        let player = self.state.turn.player;
+        if self.victory_points(player).total() >= self.state.victory_target {
+            Some(player)
+        } else {
+            None
+        }
+    }
+
+    /// Updates the holder of the Longest Road card after roads or buildings were placed.
+    ///
+    /// The official rules apply:
+    /// - A road needs at least `LONGEST_ROAD_MINIMUM` roads to claim the card.
+    /// - The holder keeps the card as long as no other road is strictly longer.
+    /// - Otherwise the card goes to the player with the strictly longest road. If several players
+    ///   tie for the longest road, or nobody reaches the minimum, the card is out of play.
+    pub fn update_longest_road(&mut self) {
+        let lengths: Vec<(Player, usize)> = self.state.players.iter()
+            .map(|player| (*player, self.longest_road(*player)))
+            .collect();
+        let longest = lengths.iter().map(|(_, length)| *length).max().unwrap_or(0);
+
+        if let Some(holder) = self.state.longest_road_holder {
+            let holder_length = lengths.iter().find(|(player, _)| *player == holder).map_or(0, |(_, length)| *length);
+            if holder_length >= LONGEST_ROAD_MINIMUM && holder_length == longest {
+                return;
+            }
+        }
+
+        let mut longest_players = lengths.iter().filter(|(_, length)| *length == longest);
+        self.state.longest_road_holder = match (longest_players.next(), longest_players.next()) {
+            (Some((player, _)), None) if longest >= LONGEST_ROAD_MINIMUM => Some(*player),
+            _ => None,
+        };
+    }
+
+    /// Returns the number of knight cards played by a player.
+    pub fn knights_played(&self, player: Player) -> usize {
+        self.state.played_knights.iter().filter(|knight| **knight == player).count()
+    }
+
+    /// Updates the holder of the Largest Army card after a knight was played.
+    ///
+    /// The first player to play `LARGEST_ARMY_MINIMUM` knights claims the card; another
+    /// player takes it over only by playing strictly more knights than the holder.
+    pub fn update_largest_army(&mut self) {
+        let mut holder = self.state.largest_army_holder;
+        let mut most = holder.map_or(LARGEST_ARMY_MINIMUM - 1, |player| self.knights_played(player));
+        for player in self.state.players.iter() {
+            let knights = self.knights_played(*player);
+            if knights > most {
+                holder = Some(*player);
+                most = knights;
+            }
+        }
+        self.state.largest_army_holder = holder;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support;
+    use crate::game::test_support::crowded_game;
+    use crate::game::{Action, Phase};
+    use crate::game::development::HeldCard;
+    use crate::game::resources::ResourceCount;
+
+    fn get_game() -> Game {
+        let mut game = crowded_game(None);
+        game.state.buildings.iter_mut().find(|building| building.intersection_id == IntersectionId(44)).unwrap().kind = BuildingKind::City;
+        game
+    }
+
+    #[test]
+    fn test_victory_points() {
+        let game = get_game();
+        assert_eq!(game.victory_points(Player::Red), VictoryPoints { settlements: 2, cities: 4, ..VictoryPoints::default() });
+        assert_eq!(game.victory_points(Player::White), VictoryPoints { settlements: 2, ..VictoryPoints::default() });
+        assert_eq!(game.victory_points(Player::Blue).total(), 1);
+
+        let mut game = get_game();
+        game.state.development_cards.push(HeldCard { player: Player::Blue, card: DevelopmentCard::VictoryPoint, bought_turn: 0 });
+        game.state.development_cards.push(HeldCard { player: Player::Blue, card: DevelopmentCard::Knight, bought_turn: 0 });
+        assert_eq!(game.victory_points(Player::Blue).development_cards, 1);
+
+        // Without Blue's settlement breaking it, White's road of 7 is the longest.
+        let mut game = get_game();
+        game.state.buildings.retain(|building| building.player != Player::Blue);
+        game.update_longest_road();
+        assert_eq!(game.victory_points(Player::White).longest_road, 2);
+    }
+
+    fn empty_game() -> Game {
+        test_support::empty_game(None)
+    }
+
+    fn add_roads(game: &mut Game, player: Player, paths: &[usize]) {
+        for path in paths {
+            game.state.roads.push(Road { id: PathId(*path), player });
+        }
+    }
+
+    #[test]
+    fn test_longest_road_holder_keeps_on_tie() {
+        let mut game = empty_game();
+        add_roads(&mut game, Player::Red, &[0, 1, 2, 3]);
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, None);
+
+        add_roads(&mut game, Player::Red, &[4]);
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, Some(Player::Red));
+
+        add_roads(&mut game, Player::Blue, &[23, 24, 25, 26, 27]);
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, Some(Player::Red));
+
+        add_roads(&mut game, Player::Blue, &[28]);
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, Some(Player::Blue));
+
+        // A red settlement breaks the blue road in two roads of 3.
+        game.state.buildings.push(Building { intersection_id: IntersectionId(19), kind: BuildingKind::Settlement, player: Player::Red });
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, Some(Player::Red));
+    }
+
+    #[test]
+    fn test_longest_road_out_of_play_when_broken_and_tied() {
+        let mut game = empty_game();
+        add_roads(&mut game, Player::Red, &[54, 55, 56, 57, 58, 59]);
+        add_roads(&mut game, Player::Blue, &[23, 24, 25, 26, 27]);
+        add_roads(&mut game, Player::White, &[0, 1, 2, 3, 4]);
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, Some(Player::Red));
+
+        game.state.buildings.push(Building { intersection_id: IntersectionId(41), kind: BuildingKind::Settlement, player: Player::Blue });
+        game.update_longest_road();
+        assert_eq!(game.state.longest_road_holder, None);
+    }
+
+    #[test]
+    fn test_longest_road_updated_by_apply() {
+        let mut game = empty_game();
+        add_roads(&mut game, Player::Red, &[0, 1, 2, 3]);
+        game.state.resources.red = ResourceCount { grain: 0, wool: 0, brick: 1, lumber: 1, ore: 0 };
+        game.state.turn.phase = Phase::Main;
+        assert!(game.apply(Action::BuildRoad { player: Player::Red, path: PathId(4) }).is_ok());
+        assert_eq!(game.state.longest_road_holder, Some(Player::Red));
+        assert_eq!(game.victory_points(Player::Red).total(), 2);
+    }
+
+    #[test]
+    fn test_largest_army_holder() {
+        let mut game = empty_game();
+        game.state.played_knights = vec![Player::Red, Player::Red, Player::Blue];
+        game.update_largest_army();
+        assert_eq!(game.state.largest_army_holder, None);
+
+        game.state.played_knights.push(Player::Red);
+        game.update_largest_army();
+        assert_eq!(game.state.largest_army_holder, Some(Player::Red));
+
+        game.state.played_knights.extend(vec![Player::Blue, Player::Blue]);
+        game.update_largest_army();
+        assert_eq!(game.state.largest_army_holder, Some(Player::Red));
+
+        game.state.played_knights.push(Player::Blue);
+        game.update_largest_army();
+        assert_eq!(game.state.largest_army_holder, Some(Player::Blue));
+        assert_eq!(game.victory_points(Player::Blue).largest_army, 2);
+    }
+
+    #[test]
+    fn test_winner() {
+        let mut game = get_game();
+        assert_eq!(game.winner(), None);
+
+        game.state.victory_target = 6;
+        assert_eq!(game.winner(), Some(Player::Red));
+
+        game.state.turn.player = Player::Blue;
+        assert_eq!(game.winner(), None);
+    }
+}
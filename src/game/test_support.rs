@@ -0,0 +1,75 @@
+//! Games shared by the unit tests.
+
+use std::convert::TryInto;
+use crate::game::{Game, Phase, Player, Turn};
+
+/// The standard board with the first settlements of Red, Blue and White and the roads of Red.
+pub(crate) const STANDARD_BOARD: &str = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo R oo R RS R oo . oo B BS . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . WS . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+
+/// The standard board later in a game, with several buildings and long roads for every player
+/// and a red city on intersection 40.
+pub(crate) const CROWDED_BOARD: &str = "
+          oo . oo . oo . oo . oo W oo W oo
+          .   10O   .   02W   .   09L   W
+     oo . oo . oo . RS R oo . oo B BS W oo . oo
+     .   12G   .   06B   .   04W   W   10B   .
+oo . oo . oo W WS . oo . oo . oo . oo W oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   W   08O   .
+oo . oo . RS R oo . oo . oo . oo . oo . WS . oo . oo
+     .   08L   .   03O   .   04G   B   05W   .
+     oo . oo . RC B oo . oo . oo . RS . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+
+/// The standard board without any pieces.
+pub(crate) const EMPTY_BOARD: &str = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo";
+
+/// Parses `board` followed by the `extra` lines below it, such as a resource table or a turn line.
+pub(crate) fn parse_game(board: &str, extra: Option<&str>) -> Game {
+    match extra {
+        Some(extra) => format!("{}\n{}", board, extra),
+        None => board.to_string(),
+    }.try_into().unwrap()
+}
+
+/// Returns the game on `STANDARD_BOARD` with the `extra` lines below it.
+pub(crate) fn standard_game(extra: Option<&str>) -> Game {
+    parse_game(STANDARD_BOARD, extra)
+}
+
+/// Returns the game on `CROWDED_BOARD` with the `extra` lines below it.
+pub(crate) fn crowded_game(extra: Option<&str>) -> Game {
+    parse_game(CROWDED_BOARD, extra)
+}
+
+/// Returns the game on `EMPTY_BOARD` with the `extra` lines below it.
+pub(crate) fn empty_game(extra: Option<&str>) -> Game {
+    parse_game(EMPTY_BOARD, extra)
+}
+
+/// Starts the main phase of `player`'s first turn, after the dice were rolled.
+pub(crate) fn main_phase(game: &mut Game, player: Player) {
+    game.state.turn = Turn { player, phase: Phase::Main, number: 1, rolled: true, development_played: false };
+}
@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::game::board::*;
+
+/// The axial coordinates of a hexagonal tile.
+///
+/// `r` counts the rows of tiles from top to bottom and `q` the tiles along a row, with the
+/// centre tile of the standard board at `(0, 0)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HexCoord {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl HexCoord {
+    /// Returns the column of the tile centre on the vertex grid.
+    ///
+    /// Neighbouring tiles in a row are two columns apart, and every row is shifted by half a tile.
+    fn column(&self) -> i32 {
+        2 * self.q + self.r
+    }
+
+    /// Returns the six corners of the tile on the vertex grid, as `(row, column)` pairs:
+    /// the three corners on top followed by the three corners at the bottom, from left to right.
+    fn corners(&self) -> [(i32, i32); 6] {
+        let x = self.column();
+        let (top, bottom) = (self.r, self.r + 1);
+        [(top, x - 1), (top, x), (top, x + 1), (bottom, x - 1), (bottom, x), (bottom, x + 1)]
+    }
+
+    /// Tells whether `other` is one of the six tiles around this one.
+    fn is_neighbour(&self, other: &HexCoord) -> bool {
+        matches!((other.q - self.q, other.r - self.r), (1, 0) | (-1, 0) | (0, 1) | (0, -1) | (1, -1) | (-1, 1))
+    }
+}
+
+/// Returns the axial coordinates of the tiles of a hexagonal board of the given radius,
+/// in reading order (row by row, left to right), which is the order of the `TileId`s.
+///
+/// The standard board has radius 2 and 19 tiles.
+pub fn hexagon(radius: i32) -> Vec<HexCoord> {
+    let mut tiles = vec![];
+    for r in -radius..=radius {
+        for q in (-radius).max(-radius - r)..=radius.min(radius - r) {
+            tiles.push(HexCoord { q, r });
+        }
+    }
+    tiles
+}
+
+/// Returns the axial coordinates of the tiles of the 5-6 player extension board, in reading order.
+///
+/// The board has rows of 3, 4, 5, 6, 5, 4 and 3 tiles, 30 tiles in all.
+pub fn extension() -> Vec<HexCoord> {
+    let mut tiles = vec![];
+    for r in -3..=3 {
+        for q in (-3).max(-3 - r)..=2.min(2 - r) {
+            tiles.push(HexCoord { q, r });
+        }
+    }
+    tiles
+}
+
+/// The paths and intersections derived from the tile coordinates of a board.
+pub(crate) struct Topology {
+    pub(crate) paths: Vec<Path>,
+    pub(crate) intersections: Vec<Intersection>,
+}
+
+impl Topology {
+    /// Derives the paths and intersections of a board from the coordinates of its tiles.
+    ///
+    /// Every tile has six corners on a grid of rows and columns. The corners are numbered in
+    /// reading order to give the `IntersectionId`s. The paths are numbered the way the ASCII
+    /// template lists them: for every row of intersections, first the paths along the row from
+    /// left to right, then the paths going down to the next row from left to right.
+    ///
+    /// # Arguments
+    /// - `tiles`: The coordinates of the tiles, indexed by `TileId`.
+    pub(crate) fn from_tiles(tiles: &[HexCoord]) -> Topology {
+        let mut corner_tiles: BTreeMap<(i32, i32), Vec<TileId>> = BTreeMap::new();
+        let mut edges: BTreeSet<((i32, i32), (i32, i32))> = BTreeSet::new();
+        for (id, tile) in tiles.iter().enumerate() {
+            let [top_left, top, top_right, bottom_left, bottom, bottom_right] = tile.corners();
+            for corner in tile.corners() {
+                corner_tiles.entry(corner).or_default().push(TileId(id));
+            }
+            for edge in [(top_left, top), (top, top_right), (bottom_left, bottom), (bottom, bottom_right),
+                         (top_left, bottom_left), (top_right, bottom_right)] {
+                edges.insert(edge);
+            }
+        }
+
+        let ids: BTreeMap<(i32, i32), usize> = corner_tiles.keys()
+            .enumerate()
+            .map(|(id, corner)| (*corner, id))
+            .collect();
+
+        // Along a row both ends share the row; going down the second end is on the next row.
+        // Sorting by (row, goes down, column) gives the template order.
+        let mut ordered: Vec<((i32, i32), (i32, i32))> = edges.into_iter().collect();
+        ordered.sort_by_key(|(a, b)| (a.0, b.0 != a.0, a.1));
+
+        let paths: Vec<Path> = ordered.iter()
+            .map(|(a, b)| Path(IntersectionId(ids[a]), IntersectionId(ids[b])))
+            .collect();
+        let intersections = corner_tiles.into_iter()
+            .map(|(corner, tiles)| {
+                let id = IntersectionId(ids[&corner]);
+                let paths = paths.iter()
+                    .enumerate()
+                    .filter(|(_, Path(a, b))| *a == id || *b == id)
+                    .map(|(path, _)| PathId(path))
+                    .collect();
+                Intersection { paths, tiles }
+            })
+            .collect();
+        Topology { paths, intersections }
+    }
+}
+
+/// Lookup tables for the neighbourhood queries on `Board`, built once from its paths and intersections.
+///
+/// - `corners`: The intersections around every tile, indexed by `TileId`.
+/// - `neighbours`: The intersections one path away from every intersection, indexed by `IntersectionId`.
+/// - `paths`: The paths ending at every intersection, indexed by `IntersectionId`.
+///
+/// All lists are in ascending order. The tables are read from the path endpoints, so they are
+/// symmetric even if an intersection fails to list one of its paths.
+#[derive(Clone)]
+pub(crate) struct Adjacency {
+    pub(crate) corners: Vec<Vec<IntersectionId>>,
+    pub(crate) neighbours: Vec<Vec<IntersectionId>>,
+    pub(crate) paths: Vec<Vec<PathId>>,
+}
+
+impl Adjacency {
+    pub(crate) fn new(paths: &[Path], intersections: &[Intersection], tiles: usize) -> Adjacency {
+        let mut corners = vec![vec![]; tiles];
+        for (id, intersection) in intersections.iter().enumerate() {
+            for tile in &intersection.tiles {
+                corners[tile.0].push(IntersectionId(id));
+            }
+        }
+
+        let mut neighbours = vec![vec![]; intersections.len()];
+        let mut ends = vec![vec![]; intersections.len()];
+        for (id, Path(a, b)) in paths.iter().enumerate() {
+            if a == b {
+                continue;
+            }
+            neighbours[a.0].push(*b);
+            neighbours[b.0].push(*a);
+            ends[a.0].push(PathId(id));
+            ends[b.0].push(PathId(id));
+        }
+        for list in neighbours.iter_mut() {
+            list.sort_by_key(|intersection: &IntersectionId| intersection.0);
+            list.dedup();
+        }
+
+        Adjacency { corners, neighbours, paths: ends }
+    }
+}
+
+impl Board {
+    /// Checks that the topology of the board is consistent.
+    ///
+    /// - Every path joins two different intersections, and both list the path.
+    /// - Every path an intersection lists ends at that intersection.
+    /// - Every intersection has two or three paths and touches one to three tiles.
+    /// - Every tile has six corners.
+    /// - Two tiles share a path exactly when they are neighbours on the hex grid.
+    /// - Every harbor sits on a path along the coast.
+    ///
+    /// # Returns
+    /// `Ok(())` for a consistent board, or an error describing the first problem found.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// assert!(board.validate().is_ok());
+    /// ```
+    pub fn validate(&self) -> Result<(), &'static str> {
+        for (id, Path(a, b)) in self.paths.iter().enumerate() {
+            if a == b {
+                return Err("A path joins an intersection to itself");
+            }
+            if a.0 >= self.intersections.len() || b.0 >= self.intersections.len() {
+                return Err("A path ends outside the board");
+            }
+            if !self.intersections[a.0].paths.contains(&PathId(id)) || !self.intersections[b.0].paths.contains(&PathId(id)) {
+                return Err("An intersection does not list a path ending there");
+            }
+        }
+
+        let mut corners = vec![0; self.tiles.len()];
+        for (id, intersection) in self.intersections.iter().enumerate() {
+            if !(2..=3).contains(&intersection.paths.len()) {
+                return Err("An intersection does not have two or three paths");
+            }
+            if !(1..=3).contains(&intersection.tiles.len()) {
+                return Err("An intersection does not touch one to three tiles");
+            }
+            for path in &intersection.paths {
+                let Path(a, b) = self.paths.get(path.0).ok_or("An intersection lists an unknown path")?;
+                if a.0 != id && b.0 != id {
+                    return Err("An intersection lists a path that does not end there");
+                }
+            }
+            for tile in &intersection.tiles {
+                *corners.get_mut(tile.0).ok_or("An intersection touches an unknown tile")? += 1;
+            }
+        }
+        if corners.iter().any(|count| *count != 6) {
+            return Err("A tile does not have six corners");
+        }
+
+        let coordinates = self.layout.coordinates();
+        let mut expected: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for (x, a) in coordinates.iter().enumerate() {
+            for (y, b) in coordinates.iter().enumerate() {
+                if a.is_neighbour(b) {
+                    expected.insert((x, y));
+                }
+            }
+        }
+        let mut adjacent: BTreeSet<(usize, usize)> = BTreeSet::new();
+        for Path(a, b) in self.paths.iter() {
+            let shared: Vec<usize> = self.intersections[a.0].tiles.iter()
+                .filter(|tile| self.intersections[b.0].tiles.contains(tile))
+                .map(|tile| tile.0)
+                .collect();
+            for x in shared.iter() {
+                for y in shared.iter().filter(|y| *y != x) {
+                    adjacent.insert((*x, *y));
+                }
+            }
+        }
+        if adjacent != expected {
+            return Err("Tile adjacency does not match the hex grid");
+        }
+
+        for Harbor { intersections: (a, b), .. } in self.harbors.iter() {
+            let on_path = self.paths.iter().any(|path| *path == Path(*a, *b) || *path == Path(*b, *a));
+            let on_coast = |id: &IntersectionId| self.intersections.get(id.0).is_some_and(|intersection| intersection.tiles.len() < 3);
+            if !on_path || !on_coast(a) || !on_coast(b) {
+                return Err("A harbor is not on the coast");
+            }
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The hand-written path table that the generated standard topology replaced. Path 10 was
+    /// written as 8-7 and is generated from its lower end as 7-8; path 61 was the self-loop 45-45
+    /// and now joins 45 to 46.
+    const BASELINE_PATHS: [(usize, usize); PATHS] = [
+        (0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 6), (0, 8), (2, 10),
+        (4, 12), (6, 14), (7, 8), (8, 9), (9, 10), (10, 11), (11, 12), (12, 13),
+        (13, 14), (14, 15), (7, 17), (9, 19), (11, 21), (13, 23), (15, 25), (16, 17),
+        (17, 18), (18, 19), (19, 20), (20, 21), (21, 22), (22, 23), (23, 24), (24, 25),
+        (25, 26), (16, 27), (18, 29), (20, 31), (22, 33), (24, 35), (26, 37), (27, 28),
+        (28, 29), (29, 30), (30, 31), (31, 32), (32, 33), (33, 34), (34, 35), (35, 36),
+        (36, 37), (28, 38), (30, 40), (32, 42), (34, 44), (36, 46), (38, 39), (39, 40),
+        (40, 41), (41, 42), (42, 43), (43, 44), (44, 45), (45, 46), (39, 47), (41, 49),
+        (43, 51), (45, 53), (47, 48), (48, 49), (49, 50), (50, 51), (51, 52), (52, 53),
+    ];
+
+    /// The paths of every intersection in the hand-written table, sorted. They already listed path 61
+    /// at both 45 and 46, so they are kept as they were.
+    const BASELINE_INTERSECTION_PATHS: [&[usize]; INTERSECTIONS] = [
+        &[0, 6], &[0, 1], &[1, 2, 7], &[2, 3], &[3, 4, 8], &[4, 5],
+        &[5, 9], &[10, 18], &[6, 10, 11], &[11, 12, 19], &[7, 12, 13], &[13, 14, 20],
+        &[8, 14, 15], &[15, 16, 21], &[9, 16, 17], &[17, 22], &[23, 33], &[18, 23, 24],
+        &[24, 25, 34], &[19, 25, 26], &[26, 27, 35], &[20, 27, 28], &[28, 29, 36], &[21, 29, 30],
+        &[30, 31, 37], &[22, 31, 32], &[32, 38], &[33, 39], &[39, 40, 49], &[34, 40, 41],
+        &[41, 42, 50], &[35, 42, 43], &[43, 44, 51], &[36, 44, 45], &[45, 46, 52], &[37, 46, 47],
+        &[47, 48, 53], &[38, 48], &[49, 54], &[54, 55, 62], &[50, 55, 56], &[56, 57, 63],
+        &[51, 57, 58], &[58, 59, 64], &[52, 59, 60], &[60, 61, 65], &[53, 61], &[62, 66],
+        &[66, 67], &[63, 67, 68], &[68, 69], &[64, 69, 70], &[70, 71], &[65, 71],
+    ];
+
+    fn desert(tiles: usize) -> Vec<Tile> {
+        (0..tiles).map(|_| Tile { dice: 0, kind: TileKind::Nothing }).collect()
+    }
+
+    #[test]
+    fn test_standard_topology() {
+        let topology = Topology::from_tiles(&hexagon(2));
+        assert_eq!(topology.paths.len(), PATHS);
+        assert_eq!(topology.intersections.len(), INTERSECTIONS);
+        assert_eq!(topology.paths[0], Path(IntersectionId(0), IntersectionId(1)));
+        assert_eq!(topology.paths[6], Path(IntersectionId(0), IntersectionId(8)));
+        assert_eq!(topology.paths[49], Path(IntersectionId(28), IntersectionId(38)));
+        assert_eq!(topology.paths[61], Path(IntersectionId(45), IntersectionId(46)));
+        assert_eq!(topology.intersections[10].paths, vec![PathId(7), PathId(12), PathId(13)]);
+        assert_eq!(topology.intersections[10].tiles.iter().map(|tile| tile.0).collect::<Vec<_>>(), vec![0, 1, 4]);
+        assert_eq!(topology.intersections[9].tiles.iter().map(|tile| tile.0).collect::<Vec<_>>(), vec![0, 3, 4]);
+    }
+
+    #[test]
+    fn test_standard_topology_keeps_the_baseline_numbering() {
+        let topology = Topology::from_tiles(&hexagon(2));
+        for (id, (a, b)) in BASELINE_PATHS.iter().enumerate() {
+            assert_eq!(topology.paths[id], Path(IntersectionId(*a), IntersectionId(*b)), "path {}", id);
+        }
+        for (id, paths) in BASELINE_INTERSECTION_PATHS.iter().enumerate() {
+            let mut generated: Vec<usize> = topology.intersections[id].paths.iter().map(|path| path.0).collect();
+            generated.sort_unstable();
+            assert_eq!(generated, paths.to_vec(), "intersection {}", id);
+        }
+    }
+
+    #[test]
+    fn test_extension_topology() {
+        let board = Board::new(desert(EXTENSION_TILES));
+        assert_eq!(board.layout, Layout::Extension);
+        assert_eq!(board.paths.len(), EXTENSION_PATHS);
+        assert_eq!(board.intersections.len(), EXTENSION_INTERSECTIONS);
+        assert_eq!(board.harbors, EXTENSION_HARBORS.to_vec());
+        assert_eq!(board.validate(), Ok(()));
+
+        // The middle row has six tiles, the corners of its left tile lie on the coast.
+        assert_eq!(board.corners_of(TileId(12)), [27, 28, 29, 40, 41, 42].map(IntersectionId));
+        assert_eq!(board.tiles_of(IntersectionId(29)), [TileId(7), TileId(12), TileId(13)]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut board = Board::new(desert(TILES));
+        assert_eq!(board.validate(), Ok(()));
+
+        board.paths[61] = Path(IntersectionId(45), IntersectionId(45));
+        assert_eq!(board.validate(), Err("A path joins an intersection to itself"));
+        board.paths[61] = Path(IntersectionId(45), IntersectionId(46));
+        assert_eq!(board.validate(), Ok(()));
+
+        board.harbors.push(Harbor { intersections: (IntersectionId(9), IntersectionId(10)), kind: HarborKind::Generic });
+        assert_eq!(board.validate(), Err("A harbor is not on the coast"));
+        board.harbors.pop();
+
+        board.intersections[9].tiles.retain(|tile| tile.0 != 0);
+        assert_eq!(board.validate(), Err("A tile does not have six corners"));
+        board.intersections[9].tiles.insert(0, TileId(0));
+        assert_eq!(board.validate(), Ok(()));
+
+        // Tiles 4 and 18 swap a corner, so every tile keeps six corners but 0 and 4 share no path.
+        assert_eq!(board.intersections[53].tiles, [TileId(18)]);
+        board.intersections[10].tiles = vec![TileId(0), TileId(1), TileId(18)];
+        board.intersections[53].tiles = vec![TileId(4)];
+        assert_eq!(board.validate(), Err("Tile adjacency does not match the hex grid"));
+    }
+
+    #[test]
+    fn test_adjacency_queries() {
+        let board = Board::new(desert(TILES));
+        let ids = |ids: &[usize]| ids.iter().map(|id| IntersectionId(*id)).collect::<Vec<_>>();
+
+        assert_eq!(board.tiles_of(IntersectionId(10)), [TileId(0), TileId(1), TileId(4)]);
+        assert_eq!(board.tiles_of(IntersectionId(0)), [TileId(0)]);
+        assert_eq!(board.corners_of(TileId(0)), ids(&[0, 1, 2, 8, 9, 10]));
+        assert_eq!(board.corners_of(TileId(9)), ids(&[20, 21, 22, 31, 32, 33]));
+        assert_eq!(board.neighbours_of(IntersectionId(10)), ids(&[2, 9, 11]));
+        assert_eq!(board.paths_of(IntersectionId(10)), [PathId(7), PathId(12), PathId(13)]);
+        assert_eq!(board.path_between(IntersectionId(1), IntersectionId(0)), Some(PathId(0)));
+        assert_eq!(board.path_between(IntersectionId(0), IntersectionId(2)), None);
+
+        for tile in 0..TILES {
+            for corner in board.corners_of(TileId(tile)) {
+                assert!(board.tiles_of(*corner).contains(&TileId(tile)));
+            }
+        }
+        for (id, Path(a, b)) in board.paths.iter().enumerate() {
+            assert_eq!(board.path_between(*a, *b), Some(PathId(id)));
+            assert!(board.neighbours_of(*b).contains(a));
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::game::actions::RuleError;
+use crate::game::board::*;
+use crate::game::resources::{Buys, PlayerResourceCount, ResourceCount, RESOURCES};
+use std::collections::HashSet;
+
+/// The number of cards given to the bank for one card without a harbor.
+pub const BANK_TRADE_RATIO: i8 = 4;
+
+/// The number of cards given to the bank for one card at a 3:1 harbor.
+pub const GENERIC_HARBOR_RATIO: i8 = 3;
+
+/// The number of cards given to the bank for one card at a 2:1 harbor of that resource.
+pub const RESOURCE_HARBOR_RATIO: i8 = 2;
+
+/// A proposal to trade cards between two players.
+///
+/// - `from`: The player making the offer.
+/// - `to`: The player the offer is made to, or `None` if any player may accept it.
+/// - `give`: The cards `from` gives.
+/// - `get`: The cards `from` receives in return.
+///
+/// Every trade involves the player whose turn it is: either they make the offer, or they
+/// are the player a counter offer is made to.
+///
+/// Example usage:
+/// ```no_run
+/// let offer = TradeOffer {
+///     from: Player::Red,
+///     to: None,
+///     give: ResourceCount::single(TileKind::Brick, 2),
+///     get: ResourceCount::single(TileKind::Ore, 1),
+/// };
+/// game.apply(Action::OfferTrade { offer })?;
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TradeOffer {
+    pub from: Player,
+    pub to: Option<Player>,
+    pub give: ResourceCount,
+    pub get: ResourceCount,
+}
+
+impl TradeOffer {
+    /// Checks that the offer exchanges cards both ways, never names the same resource on both
+    /// sides, and that `from` holds the cards it gives.
+    pub fn validate(&self, resources: &PlayerResourceCount) -> Result<(), RuleError> {
+        let overlapping = RESOURCES.iter().any(|kind| self.give[*kind] > 0 && self.get[*kind] > 0);
+        if !self.give.is_positive() || !self.get.is_positive()
+            || self.give.total() == 0 || self.get.total() == 0
+            || overlapping || self.to == Some(self.from) {
+            return Err(RuleError::IllegalTrade);
+        }
+        if !(resources[self.from] - self.give).is_positive() {
+            return Err(RuleError::NotEnoughResources);
+        }
+        Ok(())
+    }
+
+    /// Tells whether `player` holds the cards needed to accept the offer.
+    pub fn can_accept(&self, player: Player, resources: &PlayerResourceCount) -> bool {
+        player != self.from
+            && self.to.map_or(true, |to| to == player)
+            && (resources[player] - self.get).is_positive()
+    }
+
+    /// Returns the offer as seen from the other side: `player` gives what was asked for and
+    /// gets what was offered.
+    pub fn reversed(&self, player: Player) -> TradeOffer {
+        TradeOffer { from: player, to: Some(self.from), give: self.get, get: self.give }
+    }
+}
+
+impl Game {
+    /// Returns the harbors a player can use, i.e. the harbors next to one of their buildings.
+    pub fn harbors_of(&self, player: Player) -> Vec<Harbor> {
+        self.board.harbors.iter()
+            .filter(|harbor| {
+                let (a, b) = harbor.intersections;
+                self.state.buildings.iter()
+                    .any(|building| building.player == player && (building.intersection_id == a || building.intersection_id == b))
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Returns how many cards of `resource` a player has to give the bank for one card of their choice.
+    ///
+    /// The ratio is 4:1 by default, 3:1 with a building on a generic harbor and 2:1 with a
+    /// building on a harbor of `resource`.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let ratio = game.trade_ratio(Player::Red, TileKind::Ore);
+    /// println!("Red trades ore at {}:1", ratio);
+    /// ```
+    pub fn trade_ratio(&self, player: Player, resource: TileKind) -> i8 {
+        self.harbors_of(player).iter()
+            .map(|harbor| match harbor.kind {
+                HarborKind::Resource(kind) if kind == resource => RESOURCE_HARBOR_RATIO,
+                HarborKind::Resource(_) => BANK_TRADE_RATIO,
+                HarborKind::Generic => GENERIC_HARBOR_RATIO,
+            })
+            .min()
+            .unwrap_or(BANK_TRADE_RATIO)
+    }
+
+    /// Returns the trade ratio of a player for every resource, as a `ResourceCount`.
+    pub fn trade_ratios(&self, player: Player) -> ResourceCount {
+        RESOURCES.iter().fold(ResourceCount::default(), |ratios, kind| {
+            ratios + ResourceCount::single(*kind, self.trade_ratio(player, *kind))
+        })
+    }
+
+    /// Returns what a player can buy with their cards, trading surplus cards with the bank at
+    /// their trade ratios where needed.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if game.possible_buys(Player::Red).contains(&Buys::City) {
+    ///     println!("Red can build a city after trading");
+    /// }
+    /// ```
+    pub fn possible_buys(&self, player: Player) -> HashSet<Buys> {
+        self.state.resources[player].possible_buys_with_trades(self.trade_ratios(player))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::empty_game;
+    use crate::game::{Action, Event, Phase, RuleError};
+    use crate::game::resources::ResourceCount;
+
+    fn get_game() -> Game {
+        let mut game = empty_game(Some("H  0-1:?  3-4:G"));
+        game.state.buildings.push(Building { intersection_id: IntersectionId(3), kind: BuildingKind::Settlement, player: Player::Red });
+        game
+    }
+
+    #[test]
+    fn test_trade_ratio() {
+        let mut game = get_game();
+        assert_eq!(game.trade_ratio(Player::Red, TileKind::Grain), 2);
+        assert_eq!(game.trade_ratio(Player::Red, TileKind::Ore), 4);
+        assert_eq!(game.trade_ratio(Player::Blue, TileKind::Grain), 4);
+
+        game.state.buildings.push(Building { intersection_id: IntersectionId(1), kind: BuildingKind::City, player: Player::Red });
+        assert_eq!(game.trade_ratio(Player::Red, TileKind::Ore), 3);
+        assert_eq!(game.trade_ratio(Player::Red, TileKind::Grain), 2);
+        assert_eq!(game.harbors_of(Player::Red).len(), 2);
+    }
+
+    #[test]
+    fn test_trade_with_bank() {
+        let mut game = get_game();
+        game.state.turn.phase = Phase::Main;
+        game.state.resources.red = ResourceCount { grain: 2, wool: 0, brick: 4, lumber: 0, ore: 0 };
+        assert_eq!(game.possible_buys(Player::Red), vec![Buys::Road].into_iter().collect());
+
+        let trade = Action::TradeWithBank { player: Player::Red, give: TileKind::Brick, get: TileKind::Lumber };
+        assert!(game.possible_actions(Player::Red).contains(&trade));
+        assert_eq!(game.apply(trade), Ok(Event::TradedWithBank {
+            player: Player::Red,
+            gave: ResourceCount::single(TileKind::Brick, 4),
+            received: ResourceCount::single(TileKind::Lumber, 1),
+        }));
+        assert_eq!(game.state.resources.red, ResourceCount { grain: 2, wool: 0, brick: 0, lumber: 1, ore: 0 });
+        assert_eq!(game.apply(trade), Err(RuleError::NotEnoughResources));
+
+        let grain = Action::TradeWithBank { player: Player::Red, give: TileKind::Grain, get: TileKind::Ore };
+        assert!(game.apply(grain).is_ok());
+        assert_eq!(game.state.resources.red.ore, 1);
+        let same = Action::TradeWithBank { player: Player::Red, give: TileKind::Ore, get: TileKind::Ore };
+        assert_eq!(game.apply(same), Err(RuleError::IllegalTrade));
+    }
+
+    #[test]
+    fn test_trade_offer_flow() {
+        let mut game = get_game();
+        game.state.turn.phase = Phase::Main;
+        game.state.resources.red = ResourceCount::single(TileKind::Brick, 3);
+        game.state.resources.blue = ResourceCount::single(TileKind::Ore, 1);
+        game.state.resources.white = ResourceCount::single(TileKind::Ore, 2);
+        let offer = TradeOffer {
+            from: Player::Red,
+            to: None,
+            give: ResourceCount::single(TileKind::Brick, 2),
+            get: ResourceCount::single(TileKind::Ore, 1),
+        };
+        let greedy = TradeOffer { give: ResourceCount::single(TileKind::Brick, 4), ..offer };
+        assert_eq!(game.apply(Action::OfferTrade { offer: greedy }), Err(RuleError::NotEnoughResources));
+        let gift = TradeOffer { get: ResourceCount::default(), ..offer };
+        assert_eq!(game.apply(Action::OfferTrade { offer: gift }), Err(RuleError::IllegalTrade));
+        let absent = TradeOffer { to: Some(Player::Orange), ..offer };
+        assert_eq!(game.apply(Action::OfferTrade { offer: absent }), Err(RuleError::IllegalTrade));
+        assert_eq!(game.state.turn.phase, Phase::Main);
+
+        assert_eq!(game.apply(Action::OfferTrade { offer }), Ok(Event::TradeOffered { offer }));
+        assert_eq!(game.apply(Action::EndTurn { player: Player::Red }), Err(RuleError::WrongPhase));
+        assert!(game.apply(Action::RejectTrade { player: Player::Blue }).is_ok());
+        assert_eq!(game.apply(Action::AcceptTrade { player: Player::Blue }), Err(RuleError::NotYourTurn));
+
+        let counter = TradeOffer {
+            from: Player::White,
+            to: Some(Player::Red),
+            give: ResourceCount::single(TileKind::Ore, 1),
+            get: ResourceCount::single(TileKind::Brick, 3),
+        };
+        assert!(game.apply(Action::CounterTrade { offer: counter }).is_ok());
+        assert_eq!(game.state.turn.phase, Phase::Trade { offer: counter, waiting: vec![Player::Red] });
+        assert_eq!(game.apply(Action::AcceptTrade { player: Player::Red }), Ok(Event::Traded { offer: counter, with: Player::Red }));
+        assert_eq!(game.state.resources.red, ResourceCount::single(TileKind::Ore, 1));
+        assert_eq!(game.state.resources.white, ResourceCount { grain: 0, wool: 0, brick: 3, lumber: 0, ore: 1 });
+        assert_eq!(game.state.turn.phase, Phase::Main);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::game::board::{IntersectionId, Player};
+use crate::game::trade::TradeOffer;
+
+/// An enumeration of the phases a turn goes through.
+///
+/// - `Setup`: Initial placement. Players place a settlement and then a road, in snake order.
+///   `step` counts the placements already completed and `settlement` holds the settlement
+///   placed in the current step that still waits for its road.
+/// - `Roll`: The current player has to roll the dice.
+/// - `Main`: The dice were rolled; the current player may build and end the turn.
+/// - `Discard`: A 7 was rolled; the listed players still have to discard half of their cards.
+/// - `MoveRobber`: The current player has to move the robber.
+/// - `RoadBuilding`: A road building card was played; the current player may build `roads` more free roads.
+/// - `Trade`: A trade `offer` is pending; the `waiting` players may still accept, reject or counter it.
+/// - `SpecialBuilding`: The turn has ended on the 5-6 player extension board; the listed players
+///   may still build and buy development cards, one after another, before the next turn starts.
+///   The first player in the list is building and ends with `EndTurn`.
+/// - `GameOver`: A player has won; no more actions are allowed.
+///
+/// Example usage:
+/// ```no_run
+/// match game.state.turn.phase {
+///     Phase::Roll => println!("Roll the dice"),
+///     Phase::GameOver => println!("The game is over"),
+///     _ => {}
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Setup { step: usize, settlement: Option<IntersectionId> },
+    Roll,
+    Main,
+    Discard { players: Vec<Player> },
+    MoveRobber,
+    RoadBuilding { roads: u8 },
+    Trade { offer: TradeOffer, waiting: Vec<Player> },
+    SpecialBuilding { players: Vec<Player> },
+    GameOver,
+}
+
+/// Represents whose turn it is and which phase the turn is in.
+///
+/// - `number` counts the turns completed after the setup phase.
+/// - `rolled` tells whether the dice were rolled in this turn.
+/// - `development_played` tells whether a development card was played in this turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    pub player: Player,
+    pub phase: Phase,
+    pub number: u32,
+    pub rolled: bool,
+    pub development_played: bool,
+}
+
+impl Turn {
+    /// Creates the first turn of a new game for `players`, at the start of the setup phase.
+    pub fn setup(players: &[Player]) -> Turn {
+        Turn {
+            player: setup_player(players, 0),
+            phase: Phase::Setup { step: 0, settlement: None },
+            number: 0,
+            rolled: false,
+            development_played: false,
+        }
+    }
+
+    /// Creates a turn of `player` waiting for the dice roll.
+    pub fn new(player: Player) -> Turn {
+        Turn {
+            player,
+            phase: Phase::Roll,
+            number: 0,
+            rolled: false,
+            development_played: false,
+        }
+    }
+
+    /// Returns the phase the turn goes back to after a robber move or free roads:
+    /// the main phase if the dice were rolled, the roll otherwise.
+    pub fn resume(&self) -> Phase {
+        if self.rolled {
+            Phase::Main
+        } else {
+            Phase::Roll
+        }
+    }
+}
+
+/// The number of placement steps (a settlement and a road each) in the setup phase of a game of `players`.
+pub fn setup_steps(players: &[Player]) -> usize {
+    2 * players.len()
+}
+
+/// Returns the player placing in the given setup step.
+///
+/// The setup phase runs in snake order: every player places once in turn order and then
+/// once more in reverse order, e.g. Red, Blue, White, White, Blue, Red.
+pub fn setup_player(players: &[Player], step: usize) -> Player {
+    let count = players.len();
+    if step < count {
+        players[step]
+    } else {
+        players[(2 * count - 1 - step) % count]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_snake_order() {
+        let order: Vec<Player> = (0..setup_steps(&Player::THREE)).map(|step| setup_player(&Player::THREE, step)).collect();
+        assert_eq!(order, vec![Player::Red, Player::Blue, Player::White, Player::White, Player::Blue, Player::Red]);
+
+        let order: Vec<Player> = (0..setup_steps(&Player::FOUR)).map(|step| setup_player(&Player::FOUR, step)).collect();
+        assert_eq!(order[3..5], [Player::Orange, Player::Orange]);
+        assert_eq!(order[7], Player::Red);
+    }
+}
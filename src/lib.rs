@@ -1,2 +1,3 @@
 pub mod game;
 pub mod moves;
+pub mod service;
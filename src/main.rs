@@ -1,12 +1,22 @@
 extern crate fastly;
-mod game;
-mod moves;
 
-use std::convert::TryInto;
 use fastly::http::{header, Method, StatusCode};
-use fastly::{Error, Request, Response};
-use crate::game::{Game, Player};
+use fastly::{ConfigStore, Error, Request, Response};
+use settlers::service::{answer, budget, evaluator};
 
+/// The config store holding the evaluator weights of the service.
+const CONFIG_STORE: &str = "settlers";
+
+/// The config store key of the evaluator weights, one `name = value` line per weight.
+const EVALUATOR_KEY: &str = "evaluator";
+
+/// Answers a POST of a game with the action chosen for the player to act and the depth the
+/// search completed, as `action: ...` and `depth: ...` lines, or 400 if the game or the weights
+/// do not parse or the game has no turn line.
+///
+/// The search may use the `budget_ms` query parameter, capped at `MAX_BUDGET_MS`. It scores
+/// positions with the weights of the `weights` query parameter, written as `name=value` pairs
+/// separated by commas, else with the weights in the config store, else with the default weights.
 #[fastly::main]
 fn main(req: Request) -> Result<Response, Error> {
     // Log service version
@@ -17,12 +27,25 @@ fn main(req: Request) -> Result<Response, Error> {
 
     // Filter request methods...
     match req.get_method() {
+        // Search a move for the player to act in the posted game
+        &Method::POST => {
+            let budget = budget(req.get_query_parameter("budget_ms").and_then(|ms| ms.parse().ok()));
+            let config = ConfigStore::try_open(CONFIG_STORE).ok().and_then(|store| store.get(EVALUATOR_KEY));
+            let evaluator = match evaluator(req.get_query_parameter("weights"), config.as_deref()) {
+                Ok(evaluator) => evaluator,
+                Err(error) => return Ok(Response::from_status(StatusCode::BAD_REQUEST)
+                    .with_body_text_plain(&format!("Invalid weights: {}\n", error))),
+            };
+            match answer(&req.into_body_str(), budget, &evaluator) {
+                Ok(text) => Ok(Response::from_status(StatusCode::OK).with_body_text_plain(&text)),
+                Err(text) => Ok(Response::from_status(StatusCode::BAD_REQUEST).with_body_text_plain(&text)),
+            }
+        }
+
         // Block requests with unexpected methods
-        &Method::POST | &Method::PUT | &Method::PATCH | &Method::DELETE => {
-            let x: Game = req.into_body_str().try_into().unwrap();
-            println!("{:?}", x.longest_road(Player::White));
+        &Method::PUT | &Method::PATCH | &Method::DELETE => {
             Ok(Response::from_status(StatusCode::METHOD_NOT_ALLOWED)
-                .with_header(header::ALLOW, "GET, HEAD, PURGE")
+                .with_header(header::ALLOW, "GET, HEAD, POST, PURGE")
                 .with_body_text_plain("This method is not allowed\n"))
         }
 
@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use crate::game::{BuildingKind, Game, Phase, Player, TileKind};
+use crate::game::dice::roll_ways;
+
+/// The number of cards a player may hold without discarding when a 7 is rolled.
+const DISCARD_LIMIT: i32 = 7;
+
+/// The features of a position of one player, as far as an observer can see them.
+///
+/// - `victory_points`: The victory points of the player; only the player's own victory point
+///   development cards count, as the other players cannot see them.
+/// - `production`: The resource cards expected per roll from the player's buildings.
+/// - `diversity`: The number of resource kinds the player produces.
+/// - `harbors`: The number of harbors next to the player's buildings.
+/// - `longest_road`: The length of the player's longest road.
+/// - `cards`: The number of resource cards in the player's hand.
+/// - `robber_risk`: The resource cards expected to be discarded per roll, as a 7 takes half of a
+///   hand above the discard limit.
+/// - `building_spots`: The number of intersections where the player could build a settlement.
+/// - `win`: 1 if the player has won the game, otherwise 0.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Features {
+    pub victory_points: f64,
+    pub production: f64,
+    pub diversity: f64,
+    pub harbors: f64,
+    pub longest_road: f64,
+    pub cards: f64,
+    pub robber_risk: f64,
+    pub building_spots: f64,
+    pub win: f64,
+}
+
+/// Scores a position for a player as the weighted sum of its `Features`.
+///
+/// Every field is the weight of the feature with the same name; negative weights count against
+/// the player. The weights can be loaded from a configuration, one `name = value` line per
+/// weight, so they can be tuned without recompiling.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Evaluator {
+    pub victory_points: f64,
+    pub production: f64,
+    pub diversity: f64,
+    pub harbors: f64,
+    pub longest_road: f64,
+    pub cards: f64,
+    pub robber_risk: f64,
+    pub building_spots: f64,
+    pub win: f64,
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Evaluator {
+            victory_points: 10.0,
+            production: 6.0,
+            diversity: 1.0,
+            harbors: 0.5,
+            longest_road: 0.3,
+            cards: 0.5,
+            robber_risk: -3.0,
+            building_spots: 0.3,
+            win: 1000.0,
+        }
+    }
+}
+
+impl Evaluator {
+    /// Scores the position of `player` in `game`, as far as `observer` can see it.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let score = Evaluator::default().evaluate(&game, Player::Blue, Player::Red);
+    /// ```
+    pub fn evaluate(&self, game: &Game, player: Player, observer: Player) -> f64 {
+        let features = Features::of(game, player, observer);
+        self.victory_points * features.victory_points
+            + self.production * features.production
+            + self.diversity * features.diversity
+            + self.harbors * features.harbors
+            + self.longest_road * features.longest_road
+            + self.cards * features.cards
+            + self.robber_risk * features.robber_risk
+            + self.building_spots * features.building_spots
+            + self.win * features.win
+    }
+
+    /// Returns the weight called `name`, to be set from a configuration.
+    fn weight_mut(&mut self, name: &str) -> Option<&mut f64> {
+        match name {
+            "victory_points" => Some(&mut self.victory_points),
+            "production" => Some(&mut self.production),
+            "diversity" => Some(&mut self.diversity),
+            "harbors" => Some(&mut self.harbors),
+            "longest_road" => Some(&mut self.longest_road),
+            "cards" => Some(&mut self.cards),
+            "robber_risk" => Some(&mut self.robber_risk),
+            "building_spots" => Some(&mut self.building_spots),
+            "win" => Some(&mut self.win),
+            _ => None,
+        }
+    }
+}
+
+impl Features {
+    /// Measures the features of the position of `player` in `game`, as far as `observer` can see them.
+    ///
+    /// A search must not score its opponents by cards it cannot see, so the victory point
+    /// development cards of `player` only count when `player` is the observer.
+    pub fn of(game: &Game, player: Player, observer: Player) -> Features {
+        let mut production = 0.0;
+        let mut kinds = HashSet::new();
+        for building in game.state.buildings.iter().filter(|building| building.player == player) {
+            let amount = match building.kind {
+                BuildingKind::Settlement => 1.0,
+                BuildingKind::City => 2.0,
+            };
+            for id in game.board.tiles_of(building.intersection_id) {
+                let tile = &game.board.tiles[id.0];
+                if tile.kind != TileKind::Nothing && id.0 != game.state.robber.0 {
+                    production += amount * roll_ways(tile.dice) as f64 / 36.0;
+                    kinds.insert(tile.kind);
+                }
+            }
+        }
+        let cards = game.state.resources[player].total();
+        let discarded = if cards > DISCARD_LIMIT { cards / 2 } else { 0 };
+        let won = game.state.turn.phase == Phase::GameOver && game.winner() == Some(player);
+        let points = game.victory_points(player);
+        let victory_points = if player == observer { points.total() } else { points.public() };
+        Features {
+            victory_points: victory_points as f64,
+            production,
+            diversity: kinds.len() as f64,
+            harbors: game.harbors_of(player).len() as f64,
+            longest_road: game.longest_road(player) as f64,
+            cards: cards as f64,
+            robber_risk: discarded as f64 * roll_ways(7) as f64 / 36.0,
+            building_spots: game.legal_settlements(player).len() as f64,
+            win: if won { 1.0 } else { 0.0 },
+        }
+    }
+}
+
+/// Parses evaluator weights from a configuration.
+///
+/// Every line sets one weight as `name = value`. Blank lines and lines starting with `#` are
+/// ignored, and weights that are not set keep their default.
+///
+/// # Example
+/// ```rust
+/// use std::convert::TryFrom;
+///
+/// let evaluator = Evaluator::try_from("
+/// # Value ports more on this map
+/// harbors = 2.0
+/// robber_risk = -5
+/// ").unwrap();
+/// ```
+///
+/// # Errors
+/// - Returns an error if a line is not of the form `name = value`, the name is not a weight,
+///   or the value is not a finite number.
+impl TryFrom<&str> for Evaluator {
+    type Error = &'static str;
+
+    fn try_from(config: &str) -> Result<Self, Self::Error> {
+        let mut evaluator = Evaluator::default();
+        for line in config.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once('=').ok_or("Expected a line of the form name = value")?;
+            let value: f64 = value.trim().parse().map_err(|_| "Invalid weight value")?;
+            if !value.is_finite() {
+                return Err("Invalid weight value");
+            }
+            *evaluator.weight_mut(name.trim()).ok_or("Unknown weight name")? = value;
+        }
+        Ok(evaluator)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::standard_game;
+    use crate::game::development::{DevelopmentCard, HeldCard};
+    use crate::game::resources::ResourceCount;
+
+    fn get_game() -> Game {
+        standard_game(None)
+    }
+
+    #[test]
+    fn test_features() {
+        let mut game = get_game();
+        game.state.resources.red = ResourceCount::single(TileKind::Ore, 9);
+        let features = Features::of(&game, Player::Red, Player::Red);
+        assert_eq!(features.victory_points, 1.0);
+        // The settlement at 10 touches the 10O, 02W and 06B tiles.
+        assert!((features.production - 9.0 / 36.0).abs() < 1e-9);
+        assert_eq!(features.diversity, 3.0);
+        assert_eq!(features.harbors, 0.0);
+        assert_eq!(features.longest_road, 3.0);
+        assert_eq!(features.cards, 9.0);
+        assert!((features.robber_risk - 4.0 / 6.0).abs() < 1e-9);
+        assert_eq!(features.building_spots, 1.0);
+        assert_eq!(features.win, 0.0);
+    }
+
+    #[test]
+    fn test_hidden_victory_points() {
+        let mut game = get_game();
+        game.state.development_cards.push(HeldCard { player: Player::Blue, card: DevelopmentCard::VictoryPoint, bought_turn: 0 });
+        assert_eq!(Features::of(&game, Player::Blue, Player::Blue).victory_points, 2.0);
+        assert_eq!(Features::of(&game, Player::Blue, Player::Red).victory_points, 1.0);
+
+        game.state.development_cards.push(HeldCard { player: Player::Red, card: DevelopmentCard::VictoryPoint, bought_turn: 0 });
+        assert_eq!(Features::of(&game, Player::Red, Player::Red).victory_points, 2.0);
+        assert_eq!(Features::of(&game, Player::Red, Player::Blue).victory_points, 1.0);
+    }
+
+    #[test]
+    fn test_weights() {
+        let game = get_game();
+        let only_points = Evaluator {
+            victory_points: 1.0,
+            production: 0.0,
+            diversity: 0.0,
+            harbors: 0.0,
+            longest_road: 0.0,
+            cards: 0.0,
+            robber_risk: 0.0,
+            building_spots: 0.0,
+            win: 0.0,
+        };
+        assert_eq!(only_points.evaluate(&game, Player::Red, Player::Red), 1.0);
+        assert!(Evaluator::default().evaluate(&game, Player::Red, Player::Red) > Evaluator::default().evaluate(&game, Player::White, Player::Red));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let evaluator = Evaluator::try_from("
+            # Value ports more
+            harbors = 2.0
+
+            robber_risk=-5
+        ").unwrap();
+        assert_eq!(evaluator, Evaluator { harbors: 2.0, robber_risk: -5.0, ..Evaluator::default() });
+        assert_eq!(Evaluator::try_from(""), Ok(Evaluator::default()));
+        assert_eq!(Evaluator::try_from("harbors 2"), Err("Expected a line of the form name = value"));
+        assert_eq!(Evaluator::try_from("ports = 2"), Err("Unknown weight name"));
+        assert_eq!(Evaluator::try_from("harbors = two"), Err("Invalid weight value"));
+        assert_eq!(Evaluator::try_from("harbors = inf"), Err("Invalid weight value"));
+    }
+}
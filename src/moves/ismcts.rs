@@ -0,0 +1,293 @@
+use std::time::{Duration, Instant};
+use crate::game::{Action, Game, Phase, Player, TileKind};
+use crate::game::dice::Rng;
+use crate::game::resources::{ResourceCount, RESOURCES};
+use crate::moves::evaluation::Evaluator;
+use crate::moves::maximin::{index, Scores};
+
+/// The exploration constant of the UCT formula.
+const EXPLORATION: f64 = 0.7;
+
+/// The number of random actions played after leaving the tree, before the position is scored.
+const ROLLOUT_ACTIONS: usize = 40;
+
+/// How long an ISMCTS search may run.
+///
+/// - `Iterations`: Runs a fixed number of iterations; the result only depends on the seed.
+/// - `Time`: Runs iterations until the time is up, and at least one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(u32),
+    Time(Duration),
+}
+
+/// A node of the search tree, reached by playing `action`.
+///
+/// - `player`: The player who played `action`; rewards are counted from their side.
+/// - `visits`: The number of iterations that went through the node.
+/// - `available`: The number of iterations in which `action` was legal when the parent was visited.
+/// - `reward`: The sum of the rewards of `player` over the visits.
+struct Node {
+    action: Option<Action>,
+    player: Option<Player>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    visits: u32,
+    available: u32,
+    reward: f64,
+}
+
+impl Node {
+    fn new(action: Option<Action>, player: Option<Player>, parent: Option<usize>) -> Node {
+        Node { action, player, parent, children: vec![], visits: 0, available: 1, reward: 0.0 }
+    }
+
+    /// Returns the UCT value of the node: its mean reward plus an exploration bonus.
+    fn uct(&self) -> f64 {
+        let visits = self.visits.max(1) as f64;
+        self.reward / visits + EXPLORATION * ((self.available as f64).ln() / visits).sqrt()
+    }
+}
+
+impl Game {
+    /// Searches the best action for `player` with information-set Monte Carlo tree search.
+    ///
+    /// `player` does not see the resource cards and development cards of the other players,
+    /// nor the order of the development deck. Every iteration therefore plays on a
+    /// determinization: the hidden cards are dealt again at random, with every player keeping
+    /// the number of cards they hold, and the dice get a new seed. A single tree is shared by
+    /// all determinizations; its nodes are selected with UCT, counting only the iterations in
+    /// which their action was legal. After leaving the tree, random actions are played for a
+    /// while and the position is scored: 1 for the winner, otherwise each player's share of the
+    /// total given by `evaluator`.
+    ///
+    /// # Arguments
+    /// - `player`: The player to find a move for.
+    /// - `budget`: The number of iterations or the time the search may use.
+    /// - `evaluator`: Scores the positions at the end of the playouts.
+    /// - `seed`: Seeds the determinizations and the random playouts.
+    ///
+    /// # Returns
+    /// The most visited action, or `None` if `player` has nothing to do right now.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let action = game.compute_ismcts_move(Player::Red, Budget::Time(Duration::from_millis(200)), &Evaluator::default(), 7);
+    /// ```
+    pub fn compute_ismcts_move(&self, player: Player, budget: Budget, evaluator: &Evaluator, seed: u64) -> Option<Action> {
+        let actions = self.possible_actions(player);
+        if actions.len() <= 1 {
+            return actions.first().copied();
+        }
+
+        let started = Instant::now();
+        let mut rng = Rng::new(seed);
+        let mut tree = vec![Node::new(None, None, None)];
+        let mut iterations = 0;
+        loop {
+            let done = match budget {
+                Budget::Iterations(limit) => iterations >= limit,
+                Budget::Time(limit) => iterations > 0 && started.elapsed() >= limit,
+            };
+            if done {
+                break;
+            }
+            let mut game = self.determinize(player, &mut rng);
+            let leaf = select(&mut tree, &mut game, &mut rng);
+            let rewards = game.rollout(evaluator, player, &mut rng);
+            backpropagate(&mut tree, leaf, &rewards);
+            iterations += 1;
+        }
+
+        tree[0].children.iter()
+            .map(|child| &tree[*child])
+            .filter(|node| node.player == Some(player))
+            .max_by_key(|node| node.visits)
+            .and_then(|node| node.action)
+    }
+
+    /// Returns a copy of the game in which the cards `observer` cannot see are dealt again.
+    ///
+    /// The resource cards of the other players are pooled and dealt back in the same numbers.
+    /// Their development cards are shuffled with the bank's deck and dealt back likewise, and
+    /// the rest forms the new deck. The dice get a new seed drawn from `rng`.
+    pub fn determinize(&self, observer: Player, rng: &mut Rng) -> Game {
+        let mut game = self.clone();
+        let opponents: Vec<Player> = game.state.players.iter().copied().filter(|player| *player != observer).collect();
+
+        let mut cards: Vec<TileKind> = vec![];
+        for opponent in opponents.iter() {
+            let hand = game.state.resources[*opponent];
+            for kind in RESOURCES.iter() {
+                cards.extend(std::iter::repeat(*kind).take(hand[*kind].max(0) as usize));
+            }
+        }
+        rng.shuffle(&mut cards);
+        let mut cards = cards.into_iter();
+        for opponent in opponents.iter() {
+            let size = game.state.resources[*opponent].total().max(0) as usize;
+            game.state.resources[*opponent] = cards.by_ref().take(size)
+                .fold(ResourceCount::default(), |hand, kind| hand + ResourceCount::single(kind, 1));
+        }
+
+        let hidden: Vec<usize> = (0..game.state.development_cards.len())
+            .filter(|i| game.state.development_cards[*i].player != observer)
+            .collect();
+        let mut deck: Vec<_> = hidden.iter().map(|i| game.state.development_cards[*i].card).collect();
+        deck.append(&mut game.state.bank.development);
+        rng.shuffle(&mut deck);
+        for i in hidden {
+            game.state.development_cards[i].card = deck.pop().unwrap();
+        }
+        game.state.bank.development = deck;
+
+        game.state.rng = Rng::new(rng.next_u64());
+        game
+    }
+
+    /// Plays random actions from the current position and scores the result for every player,
+    /// as far as `observer` can see it.
+    fn rollout(&mut self, evaluator: &Evaluator, observer: Player, rng: &mut Rng) -> Scores {
+        for _ in 0..ROLLOUT_ACTIONS {
+            let Some(player) = self.to_move() else { break };
+            let actions = self.possible_actions(player);
+            if actions.is_empty() {
+                break;
+            }
+            let _ = self.apply(actions[rng.below(actions.len())]);
+        }
+        self.rewards(evaluator, observer)
+    }
+
+    /// Scores the position between 0 and 1 for every player.
+    fn rewards(&self, evaluator: &Evaluator, observer: Player) -> Scores {
+        let mut rewards = [0.0; Player::ALL.len()];
+        if self.state.turn.phase == Phase::GameOver {
+            if let Some(winner) = self.winner() {
+                rewards[index(winner)] = 1.0;
+            }
+            return rewards;
+        }
+        let values: Vec<(Player, f64)> = self.state.players.iter()
+            .map(|player| (*player, evaluator.evaluate(self, *player, observer).max(0.0)))
+            .collect();
+        let total: f64 = values.iter().map(|(_, value)| value).sum();
+        for (player, value) in values {
+            rewards[index(player)] = if total > 0.0 { value / total } else { 1.0 / self.state.players.len() as f64 };
+        }
+        rewards
+    }
+}
+
+/// Walks down the tree from the root, playing the selected actions on `game`, and returns the
+/// node reached. The walk stops at a new node, added for an action not tried before, or when
+/// the game has no more actions.
+fn select(tree: &mut Vec<Node>, game: &mut Game, rng: &mut Rng) -> usize {
+    let mut node = 0;
+    loop {
+        let Some(player) = game.to_move() else { return node };
+        let actions = game.possible_actions(player);
+        if actions.is_empty() {
+            return node;
+        }
+
+        let legal: Vec<usize> = tree[node].children.iter()
+            .copied()
+            .filter(|child| tree[*child].player == Some(player) && tree[*child].action.is_some_and(|action| actions.contains(&action)))
+            .collect();
+        let untried: Vec<Action> = actions.iter()
+            .copied()
+            .filter(|action| !legal.iter().any(|child| tree[*child].action == Some(*action)))
+            .collect();
+        for child in legal.iter() {
+            tree[*child].available += 1;
+        }
+
+        if !untried.is_empty() {
+            let action = untried[rng.below(untried.len())];
+            let _ = game.apply(action);
+            tree.push(Node::new(Some(action), Some(player), Some(node)));
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            return child;
+        }
+
+        let child = legal.iter()
+            .copied()
+            .max_by(|a, b| tree[*a].uct().total_cmp(&tree[*b].uct()))
+            .unwrap();
+        let _ = game.apply(tree[child].action.unwrap());
+        node = child;
+    }
+}
+
+/// Adds the result of an iteration to `leaf` and all its ancestors.
+fn backpropagate(tree: &mut [Node], leaf: usize, rewards: &Scores) {
+    let mut node = Some(leaf);
+    while let Some(id) = node {
+        tree[id].visits += 1;
+        if let Some(player) = tree[id].player {
+            tree[id].reward += rewards[index(player)];
+        }
+        node = tree[id].parent;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::{main_phase, standard_game};
+    use crate::game::IntersectionId;
+    use crate::game::development::{DevelopmentCard, HeldCard};
+    use crate::game::resources::CITY_COST;
+
+    fn get_game() -> Game {
+        standard_game(Some("   G  W  B  L  O\nW  2  0  1  0  0\nR  0  0  0  0  0\nB  0  3  0  0  1"))
+    }
+
+    #[test]
+    fn test_determinize_keeps_what_is_known() {
+        let mut game = get_game();
+        game.state.resources.red = CITY_COST;
+        game.state.development_cards = vec![
+            HeldCard { player: Player::Red, card: DevelopmentCard::Monopoly, bought_turn: 0 },
+            HeldCard { player: Player::Blue, card: DevelopmentCard::Knight, bought_turn: 0 },
+        ];
+        let mut rng = Rng::new(3);
+        let changed = (0..20).any(|_| {
+            let determinized = game.determinize(Player::Red, &mut rng);
+            assert_eq!(determinized.state.resources.red, CITY_COST);
+            assert_eq!(determinized.state.resources.blue.total(), 4);
+            assert_eq!(determinized.state.resources.white.total(), 3);
+            assert_eq!(determinized.state.resources.sum(), game.state.resources.sum());
+            assert_eq!(determinized.state.development_cards[0], game.state.development_cards[0]);
+            assert_eq!(determinized.state.development_cards[1].player, Player::Blue);
+            assert_eq!(determinized.state.bank.development.len(), game.state.bank.development.len());
+            determinized.state.resources.blue != game.state.resources.blue
+        });
+        assert!(changed);
+    }
+
+    #[test]
+    fn test_ismcts_finds_the_win() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = CITY_COST;
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        assert_eq!(game.compute_ismcts_move(Player::Red, Budget::Iterations(200), &Evaluator::default(), 1),
+                   Some(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }));
+    }
+
+    #[test]
+    fn test_ismcts_budgets() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = CITY_COST;
+        let first = game.compute_ismcts_move(Player::Red, Budget::Iterations(50), &Evaluator::default(), 9);
+        assert!(first.is_some());
+        assert_eq!(game.compute_ismcts_move(Player::Red, Budget::Iterations(50), &Evaluator::default(), 9), first);
+        assert!(game.compute_ismcts_move(Player::Red, Budget::Time(Duration::from_millis(20)), &Evaluator::default(), 9).is_some());
+        assert_eq!(game.compute_ismcts_move(Player::Blue, Budget::Iterations(50), &Evaluator::default(), 9), None);
+    }
+}
@@ -1,8 +1,395 @@
-use crate::game::Game;
+use std::time::{Duration, Instant};
+use crate::game::{Action, Event, Game, Player};
+use crate::game::dice::roll_ways;
+use crate::game::resources::{ResourceCount, RESOURCES};
+use crate::moves::evaluation::Evaluator;
+
+/// The value of a position for every player, indexed like `Player::ALL`.
+pub(crate) type Scores = [f64; Player::ALL.len()];
+
+/// Returns the position of `player` in `Player::ALL`, which indexes `Scores`.
+pub(crate) fn index(player: Player) -> usize {
+    Player::ALL.iter().position(|other| *other == player).unwrap()
+}
+
+/// Adds `scores`, weighted by `probability`, to `expected`.
+fn add_scores(expected: &mut Scores, probability: f64, scores: Scores) {
+    for (total, score) in expected.iter_mut().zip(scores) {
+        *total += probability * score;
+    }
+}
+
+/// The number of searched positions between two looks at the clock.
+const CLOCK_INTERVAL: u32 = 64;
+
+/// The result of an iterative deepening search.
+///
+/// - `action`: The best action of the deepest completed iteration, or `None` if the player has
+///   nothing to do.
+/// - `depth`: The depth of that iteration. It is 0 if the player had a single choice, or if not
+///   even the first iteration completed; `action` is then the first legal action.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Deepening {
+    pub action: Option<Action>,
+    pub depth: usize,
+}
+
+/// Tells a search when its time is up.
+///
+/// - `at`: The moment the search must stop, or `None` to search without a time limit.
+/// - `nodes`: The number of positions searched, to read the clock only every `CLOCK_INTERVAL` of them.
+/// - `passed`: Whether the deadline was seen to have passed.
+/// - `horizon`: Whether the search stopped at its depth in a position where someone was to move,
+///   i.e. whether a deeper search would see more.
+struct Deadline {
+    at: Option<Instant>,
+    nodes: u32,
+    passed: bool,
+    horizon: bool,
+}
+
+impl Deadline {
+    fn none() -> Deadline {
+        Deadline { at: None, nodes: 0, passed: false, horizon: false }
+    }
+
+    fn after(budget: Duration) -> Deadline {
+        Deadline { at: Instant::now().checked_add(budget), ..Deadline::none() }
+    }
+
+    /// Counts a searched position and returns whether the deadline has passed.
+    fn passed(&mut self) -> bool {
+        if let Some(at) = self.at {
+            self.nodes += 1;
+            if !self.passed && self.nodes % CLOCK_INTERVAL == 0 {
+                self.passed = Instant::now() >= at;
+            }
+        }
+        self.passed
+    }
+
+    /// Reads the clock at once and returns whether the deadline has passed.
+    fn expired(&mut self) -> bool {
+        if !self.passed {
+            self.passed = self.at.is_some_and(|at| Instant::now() >= at);
+        }
+        self.passed
+    }
+}
 
 impl Game {
+    /// Searches the best action for `player` with expectiminimax.
+    ///
+    /// The search looks `depth` actions ahead. Every player maximises their own score (max^n),
+    /// which is plain minimax with two players. Rolling the dice is a chance node: its value is
+    /// the average over the eleven sums, weighted by the 2d6 distribution. Buying a development
+    /// card and stealing with the robber are chance nodes as well: the search does not know the
+    /// order of the deck nor which card the game's generator will steal, so it averages over
+    /// every kind of card left in the deck and every resource in the victim's hand, weighted by
+    /// how many of them there are.
+    /// Positions at the search horizon are scored for each player by `evaluator`.
+    ///
+    /// # Arguments
+    /// - `player`: The player to find a move for.
+    /// - `depth`: The number of actions to look ahead, at least 1.
+    /// - `evaluator`: Scores the positions at the search horizon.
+    ///
+    /// # Returns
+    /// The best action, or `None` if `player` has nothing to do right now.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if let Some(action) = game.compute_best_move(Player::Red, 2, &Evaluator::default()) {
+    ///     game.apply(action)?;
+    /// }
+    /// ```
+    pub fn compute_best_move(&self, player: Player, depth: usize, evaluator: &Evaluator) -> Option<Action> {
+        let actions = self.possible_actions(player);
+        self.clone().best_action(player, &actions, depth.max(1), evaluator, &mut Deadline::none())
+    }
+
+    /// Searches the best action for `player` with iterative deepening, until `budget` is spent.
+    ///
+    /// The expectiminimax search of `compute_best_move` runs with depth 1, 2, 3 and so on. The
+    /// clock is read before every iteration and every few positions within it; no iteration
+    /// starts once the budget is spent, and an iteration that runs out of time is abandoned,
+    /// so the result is the best action of the deepest iteration that completed. The first
+    /// legal action stands in until the first iteration completes, so there is always a move to
+    /// play. Each iteration searches the previous best action first, and the deepening stops
+    /// early once a deeper search can see nothing new.
+    ///
+    /// # Arguments
+    /// - `player`: The player to find a move for.
+    /// - `budget`: The time the search may use, e.g. what is left of a request's CPU budget.
+    /// - `evaluator`: Scores the positions at the search horizon.
+    ///
+    /// # Returns
+    /// The best action found, with the depth it was searched to.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let result = game.compute_best_move_within(Player::Red, Duration::from_millis(50), &Evaluator::default());
+    /// println!("{:?} at depth {}", result.action, result.depth);
+    /// ```
+    pub fn compute_best_move_within(&self, player: Player, budget: Duration, evaluator: &Evaluator) -> Deepening {
+        let mut deadline = Deadline::after(budget);
+        let mut actions = self.possible_actions(player);
+        let mut result = Deepening { action: actions.first().copied(), depth: 0 };
+        if actions.len() <= 1 {
+            return result;
+        }
+
+        let mut game = self.clone();
+        for depth in 1.. {
+            if deadline.expired() {
+                break;
+            }
+            deadline.horizon = false;
+            let action = game.best_action(player, &actions, depth, evaluator, &mut deadline);
+            if deadline.passed {
+                break;
+            }
+            result = Deepening { action, depth };
+            if let Some(position) = actions.iter().position(|other| Some(*other) == action) {
+                actions[..=position].rotate_right(1);
+            }
+            if !deadline.horizon {
+                break;
+            }
+        }
+        result
+    }
+
+    /// Returns the first of `actions` with the best value for `player`, searching `depth` actions ahead.
+    fn best_action(&mut self, player: Player, actions: &[Action], depth: usize, evaluator: &Evaluator, deadline: &mut Deadline) -> Option<Action> {
+        let mut best: Option<(Action, f64)> = None;
+        for action in actions.iter().copied() {
+            let value = self.action_value(action, depth - 1, evaluator, player, deadline)[index(player)];
+            if best.map_or(true, |(_, best_value)| value > best_value) {
+                best = Some((action, value));
+            }
+        }
+        best.map(|(action, _)| action)
+    }
+
+    /// Returns the scores after `action`, searching `depth` more actions. The state is restored afterwards.
+    fn action_value(&mut self, action: Action, depth: usize, evaluator: &Evaluator, observer: Player, deadline: &mut Deadline) -> Scores {
+        let saved = self.state.clone();
+        let mut expected = [0.0; Player::ALL.len()];
+        match action {
+            Action::RollDice { .. } => {
+                for roll in 2..=12 {
+                    self.state = saved.clone();
+                    self.resolve_roll(roll);
+                    let probability = roll_ways(roll) as f64 / 36.0;
+                    add_scores(&mut expected, probability, self.search(depth, evaluator, observer, deadline));
+                }
+            }
+            Action::BuyDevelopmentCard { .. } if !saved.bank.development.is_empty() => {
+                let deck = &saved.bank.development;
+                for (i, kind) in deck.iter().enumerate() {
+                    if deck[..i].contains(kind) {
+                        continue;
+                    }
+                    self.state = saved.clone();
+                    let top = self.state.bank.development.len() - 1;
+                    self.state.bank.development.swap(i, top);
+                    let probability = deck.iter().filter(|card| *card == kind).count() as f64 / deck.len() as f64;
+                    let scores = self.outcome_value(action, depth, evaluator, observer, deadline);
+                    add_scores(&mut expected, probability, scores);
+                }
+            }
+            Action::MoveRobber { player, victim: Some(victim), .. } if saved.resources[victim].total() > 0 => {
+                let hand = saved.resources[victim];
+                for kind in RESOURCES.iter().copied().filter(|kind| hand[*kind] > 0) {
+                    self.state = saved.clone();
+                    let scores = match self.apply(action) {
+                        Ok(event) => {
+                            // Swap whatever the generator stole for the card of this outcome.
+                            if let Event::RobberMoved { stolen: Some(stolen), .. } = event {
+                                let (stolen, wanted) = (ResourceCount::single(stolen, 1), ResourceCount::single(kind, 1));
+                                self.state.resources[victim] = self.state.resources[victim] + stolen - wanted;
+                                self.state.resources[player] = self.state.resources[player] - stolen + wanted;
+                            }
+                            self.search(depth, evaluator, observer, deadline)
+                        }
+                        Err(_) => self.scores(evaluator, observer),
+                    };
+                    let probability = hand[kind] as f64 / hand.total() as f64;
+                    add_scores(&mut expected, probability, scores);
+                }
+            }
+            _ => expected = self.outcome_value(action, depth, evaluator, observer, deadline),
+        }
+        self.state = saved;
+        expected
+    }
+
+    /// Applies `action` and returns the scores after searching `depth` more actions.
+    fn outcome_value(&mut self, action: Action, depth: usize, evaluator: &Evaluator, observer: Player, deadline: &mut Deadline) -> Scores {
+        match self.apply(action) {
+            Ok(_) => self.search(depth, evaluator, observer, deadline),
+            Err(_) => self.scores(evaluator, observer),
+        }
+    }
+
+    /// Returns the scores of the current position when every player plays their best for `depth` actions.
+    ///
+    /// Once the deadline has passed, the search returns at once with meaningless scores.
+    fn search(&mut self, depth: usize, evaluator: &Evaluator, observer: Player, deadline: &mut Deadline) -> Scores {
+        if deadline.passed() {
+            return [0.0; Player::ALL.len()];
+        }
+        let player = match self.to_move() {
+            Some(player) if depth > 0 => player,
+            Some(_) => {
+                deadline.horizon = true;
+                return self.scores(evaluator, observer);
+            }
+            None => return self.scores(evaluator, observer),
+        };
+        let mut best: Option<Scores> = None;
+        for action in self.possible_actions(player) {
+            let scores = self.action_value(action, depth - 1, evaluator, observer, deadline);
+            if best.map_or(true, |best| scores[index(player)] > best[index(player)]) {
+                best = Some(scores);
+            }
+        }
+        best.unwrap_or_else(|| self.scores(evaluator, observer))
+    }
+
+    /// Scores the current position for every player in the game, as far as `observer` can see it.
+    fn scores(&self, evaluator: &Evaluator, observer: Player) -> Scores {
+        let mut scores = [0.0; Player::ALL.len()];
+        for player in self.state.players.iter() {
+            scores[index(*player)] = evaluator.evaluate(self, *player, observer);
+        }
+        scores
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::{main_phase, standard_game};
+    use crate::game::{IntersectionId, Phase, RobberId, TileKind, Turn};
+    use crate::game::development::DevelopmentCard;
+    use crate::game::dice::Rng;
+    use crate::game::resources::{CITY_COST, DEVELOPMENT_COST, SETTLEMENT_COST};
+
+    fn get_game() -> Game {
+        standard_game(None)
+    }
+
+    #[test]
+    fn test_roll_distribution() {
+        assert_eq!((2..=12).map(|roll| roll_ways(roll) as u32).sum::<u32>(), 36);
+        assert_eq!(roll_ways(7), 6);
+        assert_eq!(roll_ways(1), 0);
+    }
+
+    #[test]
+    fn test_best_move_wins_the_game() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = CITY_COST;
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        assert_eq!(game.compute_best_move(Player::Red, 2, &Evaluator::default()),
+                   Some(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }));
+    }
+
+    #[test]
+    fn test_best_move_builds_on_production() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = SETTLEMENT_COST + ResourceCount::single(TileKind::Lumber, 1) + ResourceCount::single(TileKind::Brick, 1);
+        assert_eq!(game.compute_best_move(Player::Red, 1, &Evaluator::default()),
+                   Some(Action::BuildSettlement { player: Player::Red, intersection: IntersectionId(8) }));
+        assert_eq!(game.state.resources.red, SETTLEMENT_COST + ResourceCount::single(TileKind::Lumber, 1) + ResourceCount::single(TileKind::Brick, 1));
+    }
+
+    #[test]
+    fn test_best_move_upgrades_to_a_city() {
+        let game = standard_game(Some("T  R  main  4  1  0\n   G  W  B  L  O\nR  3  0  0  0  2"));
+        assert_eq!(game.compute_best_move(Player::Red, 2, &Evaluator::default()),
+                   Some(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }));
+    }
+
+    #[test]
+    fn test_best_move_rolls_the_dice() {
+        let mut game = get_game();
+        assert_eq!(game.compute_best_move(Player::Red, 2, &Evaluator::default()), Some(Action::RollDice { player: Player::Red }));
+        assert_eq!(game.compute_best_move(Player::Blue, 2, &Evaluator::default()), None);
+
+        game.state.turn.phase = Phase::GameOver;
+        assert_eq!(game.compute_best_move(Player::Red, 2, &Evaluator::default()), None);
+    }
+
+    #[test]
+    fn test_deepening_within_budget() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = CITY_COST;
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        let result = game.compute_best_move_within(Player::Red, Duration::from_millis(200), &Evaluator::default());
+        assert_eq!(result.action, Some(Action::BuildCity { player: Player::Red, intersection: IntersectionId(10) }));
+        assert!(result.depth >= 1);
+    }
+
+    #[test]
+    fn test_deepening_always_has_a_move() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = CITY_COST + SETTLEMENT_COST;
+        let first = game.possible_actions(Player::Red)[0];
+        assert!(game.possible_actions(Player::Red).len() > 1);
+        assert_eq!(game.compute_best_move_within(Player::Red, Duration::ZERO, &Evaluator::default()),
+                   Deepening { action: Some(first), depth: 0 });
+
+        let game = get_game();
+        assert_eq!(game.compute_best_move_within(Player::Red, Duration::ZERO, &Evaluator::default()),
+                   Deepening { action: Some(Action::RollDice { player: Player::Red }), depth: 0 });
+        assert_eq!(game.compute_best_move_within(Player::Blue, Duration::ZERO, &Evaluator::default()),
+                   Deepening { action: None, depth: 0 });
+    }
+
+    #[test]
+    fn test_development_card_is_a_chance_node() {
+        let mut game = get_game();
+        main_phase(&mut game, Player::Red);
+        game.state.resources.red = DEVELOPMENT_COST;
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        let buy = Action::BuyDevelopmentCard { player: Player::Red };
+        let value = |game: &Game| game.clone().action_value(buy, 0, &Evaluator::default(), Player::Red, &mut Deadline::none())[index(Player::Red)];
+
+        game.state.bank.development = vec![DevelopmentCard::VictoryPoint, DevelopmentCard::Knight, DevelopmentCard::Knight, DevelopmentCard::Knight];
+        let winning_last = value(&game);
+        game.state.bank.development.reverse();
+        assert_eq!(value(&game), winning_last);
+        game.state.bank.development = vec![DevelopmentCard::Knight; 4];
+        assert!(value(&game) < winning_last);
+    }
+
+    #[test]
+    fn test_steal_is_a_chance_node() {
+        let mut game = get_game();
+        game.state.turn = Turn { player: Player::Red, phase: Phase::MoveRobber, number: 1, rolled: true, development_played: false };
+        game.state.resources.red = CITY_COST - ResourceCount::single(TileKind::Ore, 1);
+        game.state.resources.blue = ResourceCount::single(TileKind::Ore, 1) + ResourceCount::single(TileKind::Brick, 1);
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        let robber = game.board.tiles_of(IntersectionId(13)).iter()
+            .map(|tile| RobberId(tile.0))
+            .find(|robber| *robber != game.state.robber)
+            .unwrap();
+        let steal = Action::MoveRobber { player: Player::Red, robber, victim: Some(Player::Blue) };
 
-    pub fn compute_best_move() -> &'static str {
-        ""
-    }   
-}
\ No newline at end of file
+        let values: Vec<f64> = (0..8).map(|seed| {
+            game.state.rng = Rng::new(seed);
+            game.clone().action_value(steal, 1, &Evaluator::default(), Player::Red, &mut Deadline::none())[index(Player::Red)]
+        }).collect();
+        assert!(values.iter().all(|value| *value == values[0]));
+        // Half of the time Red steals the ore and wins with a city.
+        assert!(values[0] > Evaluator::default().win / 4.0 && values[0] < Evaluator::default().win);
+    }
+}
@@ -1,3 +1,7 @@
-
 mod possible_moves;
-pub mod maximin;
\ No newline at end of file
+mod trading;
+pub mod evaluation;
+pub mod maximin;
+pub mod ismcts;
+pub mod self_play;
+pub mod placement;
@@ -0,0 +1,245 @@
+use std::collections::HashSet;
+use crate::game::{Game, HarborKind, IntersectionId, Path, PathId, Phase, Player, TileKind};
+use crate::game::dice::roll_ways;
+use crate::game::resources::RESOURCES;
+use crate::game::turn::{setup_player, setup_steps};
+
+/// The value of one resource card expected per roll from the settlement.
+const PRODUCTION_WEIGHT: f64 = 10.0;
+
+/// The value of every resource kind the settlement produces that the player did not produce yet.
+const NEW_KIND_WEIGHT: f64 = 0.5;
+
+/// The value of a 3:1 harbor at the settlement.
+const GENERIC_HARBOR_VALUE: f64 = 0.5;
+
+/// The value of a 2:1 harbor at the settlement per card of its resource the player expects per roll.
+const RESOURCE_HARBOR_WEIGHT: f64 = 5.0;
+
+/// The share of the best spot left for the player's next setup settlement that counts for this one.
+const NEXT_SETTLEMENT_WEIGHT: f64 = 0.5;
+
+/// The share of the best spot the road leads to that counts for the road.
+const ROAD_WEIGHT: f64 = 0.25;
+
+/// The expected resource cards per roll from a spot, indexed like `RESOURCES`.
+type Production = [f64; RESOURCES.len()];
+
+/// A settlement and the road next to it for the setup phase, with its score.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Placement {
+    pub settlement: IntersectionId,
+    pub road: PathId,
+    pub score: f64,
+}
+
+impl Game {
+    /// Ranks every settlement and road the player to place in the setup phase can choose.
+    ///
+    /// A settlement scores for the cards it is expected to produce per roll, for the resource
+    /// kinds it adds to what the player already produces, and for its harbor: a 3:1 harbor
+    /// always helps, a 2:1 harbor as much as the player produces of its resource. The other
+    /// players are expected to take the best spots left, in snake order, until the player
+    /// places again; the best spot left after them counts towards a first settlement, as it
+    /// will likely be the second one. A road scores for the best spot left one road further.
+    ///
+    /// Once the settlement of a step is placed, only the roads next to it are ranked.
+    ///
+    /// # Arguments
+    /// - `player`: The player placing in the current setup step.
+    ///
+    /// # Returns
+    /// The placements from best to worst, or an empty `Vec` if `player` is not placing now.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if let Some(best) = game.rank_placements(Player::Red).first() {
+    ///     println!("Settle at {:?} with a road on {:?} ({:.2})", best.settlement, best.road, best.score);
+    /// }
+    /// ```
+    pub fn rank_placements(&self, player: Player) -> Vec<Placement> {
+        let (step, placed) = match self.state.turn.phase {
+            Phase::Setup { step, settlement } if self.state.turn.player == player => (step, settlement),
+            _ => return vec![],
+        };
+        let candidates = match placed {
+            Some(settlement) => vec![settlement],
+            None => self.setup_intersections(),
+        };
+        let open: HashSet<IntersectionId> = self.setup_intersections().into_iter().collect();
+        let owned = self.state.buildings.iter()
+            .filter(|building| building.player == player && Some(building.intersection_id) != placed)
+            .fold([0.0; RESOURCES.len()], |total, building| add(total, self.spot_production(building.intersection_id)));
+        let contested = contested_steps(&self.state.players, player, step);
+        let places_again = (step + 1..setup_steps(&self.state.players)).any(|later| setup_player(&self.state.players, later) == player);
+
+        let mut placements = vec![];
+        for settlement in candidates {
+            let production = self.spot_production(settlement);
+            let produced = add(owned, production);
+            let mut left = open.clone();
+            left.remove(&settlement);
+            for neighbour in self.board.neighbours_of(settlement) {
+                left.remove(neighbour);
+            }
+            for _ in 0..contested {
+                self.take_best_spot(&mut left);
+            }
+
+            let mut score = spot_value(production, owned) + self.harbor_value(settlement, produced);
+            if places_again {
+                score += NEXT_SETTLEMENT_WEIGHT * left.iter()
+                    .map(|spot| spot_value(self.spot_production(*spot), produced))
+                    .fold(0.0, f64::max);
+            }
+
+            for road in self.board.paths_of(settlement) {
+                if self.state.roads.iter().any(|other| other.id == *road) {
+                    continue;
+                }
+                let Path(a, b) = self.board.paths[road.0];
+                let end = if a == settlement { b } else { a };
+                let reachable = self.board.neighbours_of(end).iter()
+                    .filter(|spot| left.contains(spot))
+                    .map(|spot| spot_value(self.spot_production(*spot), produced))
+                    .fold(0.0, f64::max);
+                placements.push(Placement { settlement, road: *road, score: score + ROAD_WEIGHT * reachable });
+            }
+        }
+        placements.sort_by(|a, b| b.score.total_cmp(&a.score));
+        placements
+    }
+
+    /// Returns the resource cards a settlement on `intersection` is expected to produce per roll.
+    fn spot_production(&self, intersection: IntersectionId) -> Production {
+        let mut production = [0.0; RESOURCES.len()];
+        for id in self.board.tiles_of(intersection) {
+            let tile = &self.board.tiles[id.0];
+            if id.0 == self.state.robber.0 {
+                continue;
+            }
+            if let Some(kind) = RESOURCES.iter().position(|kind| *kind == tile.kind) {
+                production[kind] += roll_ways(tile.dice) as f64 / 36.0;
+            }
+        }
+        production
+    }
+
+    /// Returns the value of the harbors at `intersection` for a player producing `produced`.
+    fn harbor_value(&self, intersection: IntersectionId, produced: Production) -> f64 {
+        self.board.harbors.iter()
+            .filter(|harbor| harbor.intersections.0 == intersection || harbor.intersections.1 == intersection)
+            .map(|harbor| match harbor.kind {
+                HarborKind::Generic => GENERIC_HARBOR_VALUE,
+                HarborKind::Resource(kind) => RESOURCE_HARBOR_WEIGHT * produced[resource_index(kind)],
+            })
+            .sum()
+    }
+
+    /// Takes the most productive spot out of `spots`, together with its neighbours.
+    fn take_best_spot(&self, spots: &mut HashSet<IntersectionId>) {
+        let best = spots.iter()
+            .copied()
+            .map(|spot| (spot, self.spot_production(spot).iter().sum::<f64>()))
+            .max_by(|(a, a_total), (b, b_total)| a_total.total_cmp(b_total).then(b.0.cmp(&a.0)));
+        if let Some((spot, _)) = best {
+            spots.remove(&spot);
+            for neighbour in self.board.neighbours_of(spot) {
+                spots.remove(neighbour);
+            }
+        }
+    }
+}
+
+/// Returns the number of settlements the other players place after `step` before `player`
+/// places again, or until the end of the setup phase if `player` does not place again.
+fn contested_steps(players: &[Player], player: Player, step: usize) -> usize {
+    (step + 1..setup_steps(players))
+        .take_while(|later| setup_player(players, *later) != player)
+        .count()
+}
+
+/// Scores a spot producing `production` for a player who already produces `owned`.
+fn spot_value(production: Production, owned: Production) -> f64 {
+    let new_kinds = production.iter().zip(owned.iter()).filter(|(new, old)| **new > 0.0 && **old == 0.0).count();
+    PRODUCTION_WEIGHT * production.iter().sum::<f64>() + NEW_KIND_WEIGHT * new_kinds as f64
+}
+
+fn add(a: Production, b: Production) -> Production {
+    let mut total = a;
+    for (total, more) in total.iter_mut().zip(b.iter()) {
+        *total += more;
+    }
+    total
+}
+
+fn resource_index(kind: TileKind) -> usize {
+    RESOURCES.iter().position(|other| *other == kind).unwrap()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::empty_game;
+    use crate::game::{Action, RobberId, Turn};
+
+    fn get_game() -> Game {
+        let mut game = empty_game(None);
+        game.state.robber = RobberId(9);
+        game.state.turn = Turn::setup(&game.state.players);
+        game
+    }
+
+    #[test]
+    fn test_contested_steps() {
+        // Red, Blue, White, White, Blue, Red
+        assert_eq!(contested_steps(&Player::THREE, Player::Red, 0), 4);
+        assert_eq!(contested_steps(&Player::THREE, Player::Blue, 1), 2);
+        assert_eq!(contested_steps(&Player::THREE, Player::White, 2), 0);
+        assert_eq!(contested_steps(&Player::THREE, Player::Blue, 4), 1);
+        assert_eq!(contested_steps(&Player::THREE, Player::Red, 5), 0);
+    }
+
+    #[test]
+    fn test_rank_first_placements() {
+        let game = get_game();
+        let placements = game.rank_placements(Player::Red);
+        let pairs: usize = game.setup_intersections().iter().map(|spot| game.board.paths_of(*spot).len()).sum();
+        assert_eq!(placements.len(), pairs);
+        assert!(placements.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        let best = placements[0];
+        let best_production: f64 = game.spot_production(best.settlement).iter().sum();
+        assert!(game.setup_intersections().iter().all(|spot| game.spot_production(*spot).iter().sum::<f64>() <= best_production + 2.0 / 36.0));
+        assert!(game.board.paths_of(best.settlement).contains(&best.road));
+
+        assert!(game.rank_placements(Player::Blue).is_empty());
+    }
+
+    #[test]
+    fn test_rank_roads_of_placed_settlement() {
+        let mut game = get_game();
+        let best = game.rank_placements(Player::Red)[0];
+        game.apply(Action::BuildSettlement { player: Player::Red, intersection: best.settlement }).unwrap();
+        let roads = game.rank_placements(Player::Red);
+        assert_eq!(roads.len(), game.board.paths_of(best.settlement).len());
+        assert!(roads.iter().all(|placement| placement.settlement == best.settlement));
+        assert_eq!(roads[0], best);
+    }
+
+    #[test]
+    fn test_second_placement_complements_the_first() {
+        let mut game = get_game();
+        for step in 0..setup_steps(&game.state.players) - 1 {
+            let player = setup_player(&game.state.players, step);
+            let best = game.rank_placements(player)[0];
+            game.apply(Action::BuildSettlement { player, intersection: best.settlement }).unwrap();
+            game.apply(Action::BuildRoad { player, path: best.road }).unwrap();
+        }
+        let owned = game.spot_production(game.state.buildings[0].intersection_id);
+        let best = game.rank_placements(Player::Red)[0];
+        let second = game.spot_production(best.settlement);
+        assert!(second.iter().zip(owned.iter()).any(|(new, old)| *new > 0.0 && *old == 0.0));
+    }
+}
@@ -1,94 +1,50 @@
-use crate::game::{Building, IntersectionId, Path, Player};
+use crate::game::{Action, Building, BuildingKind, IntersectionId, Path, PathId, Phase, Player, RobberId};
+use crate::game::development::DevelopmentCard;
+use crate::game::resources::{ResourceCount, CITY_COST, DEVELOPMENT_COST, RESOURCES, ROAD_COST, SETTLEMENT_COST};
 use std::collections::{HashMap, HashSet};
 use crate::game::Game;
 impl Game {
-    /// Calculates the longest road using Depth-First Search (DFS).
+    /// Calculates the length of the longest trail that starts at `node`, using Depth-First Search (DFS).
     ///
-    /// This method assumes that the graph is acyclic, making the problem analogous to finding the diameter
-    /// of an N-ary tree. If the graph contains cycles, the problem becomes NP-complete, and this method 
-    /// will not provide a valid result.
-    ///
-    /// # Methodology
-    /// The function traverses the graph recursively to calculate the longest path by identifying
-    /// the two longest branches extending from each node. These branches are used to compute the longest
-    /// road as the sum of their lengths.
+    /// A trail may visit an intersection several times but never uses the same road twice,
+    /// which is how the official rules count loops and forks. A trail that reaches an
+    /// intersection in `blocked` ends there.
     ///
     /// # Arguments
-    /// - `node`: The current node in the graph where the DFS starts.
-    /// - `graph`: A `HashMap` representing the adjacency list of the graph. The keys are node indices,
-    ///   and the values are vectors of connected node indices.
-    /// - `visited`: A mutable reference to a `HashSet` that keeps track of visited nodes to prevent cycles
-    ///   and redundant computations.
-    /// - `longest`: The current longest path discovered during the DFS traversal.
+    /// - `node`: The intersection the trail continues from.
+    /// - `graph`: The adjacency list of the player's roads, as built by `road_graph`.
+    /// - `used`: The roads already used by the trail, as ordered pairs of intersections.
+    /// - `blocked`: The intersections occupied by buildings of other players.
     ///
     /// # Returns
-    /// A tuple `(usize, usize)`:
-    /// - The first element represents the height of the current DFS branch (i.e., the maximum depth from the `node`).
-    /// - The second element represents the updated longest path across the graph.
-    ///
-    /// # Examples
-    /// ```no_run
-    /// use std::collections::{HashMap, HashSet};
-    ///
-    /// // Example graph (acyclic tree structure)
-    /// let mut graph: HashMap<usize, Vec<usize>> = HashMap::new();
-    /// graph.insert(0, vec![1, 2]);
-    /// graph.insert(1, vec![0, 3, 4]);
-    /// graph.insert(2, vec![0]);
-    /// graph.insert(3, vec![1]);
-    /// graph.insert(4, vec![1]);
-    ///
-    /// let mut visited = HashSet::new();
-    /// let initial_longest = 0;
-    ///
-    /// // Assume `dfs` is implemented in the context of a struct with `self`
-    /// let (branch_height, longest_path) = some_struct.dfs(0, &graph, &mut visited, initial_longest);
-    ///
-    /// println!("Branch height: {}", branch_height);
-    /// println!("Longest path: {}", longest_path);
-    /// ```
+    /// The number of roads in the longest trail continuing from `node`.
     ///
     /// # Complexity
-    /// - **Time Complexity**: `O(V + E)`, where `V` is the number of vertices (nodes) and `E` is the number of edges in the graph.
-    /// - **Space Complexity**: `O(V)` due to the `visited` set and recursion stack.
-    ///
-    /// # Assumptions
-    /// - The graph is connected and acyclic.
-    /// - Nodes are represented as `usize` indices.
-    ///
-    /// # Notes
-    /// - If the graph contains cycles, the method will not compute the correct result.
-    /// - To extend this method for cyclic graphs, additional logic (e.g., cycle detection or pruning) is required.
-    ///
-    /// # Limitations
-    /// - The method does not verify if the graph is acyclic; it relies on the caller to provide a valid input.
-    fn dfs(&self, node: usize, graph: &HashMap<usize, Vec<usize>>, visited: &mut HashSet<usize>, longest: usize) -> (usize, usize) {
-        if visited.contains(&node) {
-            return (0, 0);
-        }
-        visited.insert(node);
-        let mut max1 = 0;
-        let mut max2 = 0;
-        for node2 in graph[&node].clone() {
-            let (height, _) = self.dfs(node2, graph, visited, longest);
-            if max1 < height {
-                max2 = max1;
-                max1 = height;
-            } else if max2 < height {
-                max2 = height
+    /// Exponential in the worst case, but a player owns at most 15 roads and every
+    /// intersection has at most 3 of them, so the search stays small.
+    fn longest_trail(&self, node: usize, graph: &HashMap<usize, Vec<usize>>, used: &mut HashSet<(usize, usize)>, blocked: &HashSet<usize>) -> usize {
+        let mut longest = 0;
+        for next in graph[&node].iter() {
+            let edge = (node.min(*next), node.max(*next));
+            if used.contains(&edge) {
+                continue;
             }
+            used.insert(edge);
+            let length = if blocked.contains(next) {
+                1
+            } else {
+                1 + self.longest_trail(*next, graph, used, blocked)
+            };
+            used.remove(&edge);
+            longest = longest.max(length);
         }
-        let longest = if max1 + max2 > longest {
-            max1 + max2
-        } else {
-            longest
-        };
-        (max1 + 1, longest)
+        longest
     }
 
     /// Calculates the longest road for a given player.
     ///
-    /// Uses depth-first search (DFS) to find the longest connected path of roads owned by the player.
+    /// Tries every intersection of every road network of the player as the start of a trail
+    /// and keeps the longest. Roads are broken by intersections occupied by buildings of other players.
     ///
     /// # Arguments
     /// - `player`: The player whose roads are being evaluated.
@@ -101,11 +57,16 @@ impl Game {
     /// let longest_road = game.longest_road(Player::Red);
     /// println!("Longest road: {}", longest_road);
     /// ```
-    pub(crate) fn longest_road(&self, player: Player) -> usize {
+    pub fn longest_road(&self, player: Player) -> usize {
         let graph = self.road_graph(player);
-        let (_, road_length) = self.dfs(6, &graph, &mut HashSet::new(), 0);
-
-        road_length
+        let blocked: HashSet<usize> = self.state.buildings.iter()
+            .filter(|building| building.player != player)
+            .map(|building| building.intersection_id.0)
+            .collect();
+        graph.keys()
+            .map(|node| self.longest_trail(*node, &graph, &mut HashSet::new(), &blocked))
+            .max()
+            .unwrap_or(0)
     }
 
     /// Constructs a graph of roads owned by the given player.
@@ -122,43 +83,57 @@ impl Game {
         for road in &self.state.roads {
             if road.player == player {
                 let Path(IntersectionId(a), IntersectionId(b)) = self.board.paths[road.id.0];
-                graph.entry(a).or_insert_with(Vec::new).push(b);
-                graph.entry(b).or_insert_with(Vec::new).push(a);
+                graph.entry(a).or_default().push(b);
+                graph.entry(b).or_default().push(a);
             }
         }
         graph
     }
 
-    /// Identifies intersections where the given player can build a new building.
+    /// Identifies intersections where the given player can build a new settlement after the setup phase.
     ///
-    /// Ensures that buildings are not placed too close to each other.
+    /// The intersection must be empty, none of its neighbours may hold a building (the distance
+    /// rule), and one of the player's roads must end there.
     ///
     /// # Arguments
     /// - `player`: The player attempting to build.
     ///
     /// # Returns
-    /// A `HashSet` of `IntersectionId`s where the player can build.
-    pub(crate) fn possible_building_intersections(&self, player: Player) -> HashSet<IntersectionId> {
-        let too_close_intersections= self.too_close_intersections();
-        let mut possible_building_intersections: HashSet<IntersectionId> = HashSet::new();
-        for road in self.state.roads.iter().filter(|road| road.player == player) {
-            let Path(intersection_a, intersection_b) =  self.board.paths[road.id.0];
-            if !too_close_intersections.contains(&intersection_a) {
-                possible_building_intersections.insert(intersection_a);
-            }
-            if !too_close_intersections.contains(&intersection_b) {
-                possible_building_intersections.insert(intersection_b);
-            }
+    /// The legal `IntersectionId`s in ascending order.
+    pub fn legal_settlements(&self, player: Player) -> Vec<IntersectionId> {
+        let too_close = self.too_close_intersections();
+        let road_ends: HashSet<usize> = self.road_graph(player).keys().copied().collect();
+        (0..self.board.intersections.len())
+            .filter(|id| road_ends.contains(id))
+            .map(IntersectionId)
+            .filter(|intersection| !too_close.contains(intersection))
+            .collect()
+    }
+
+    /// Identifies the settlements the given player can upgrade to a city.
+    ///
+    /// Every settlement of the player qualifies as long as the player has a city piece left.
+    ///
+    /// # Returns
+    /// The `IntersectionId`s of the settlements in ascending order.
+    pub fn legal_cities(&self, player: Player) -> Vec<IntersectionId> {
+        if self.remaining_pieces(player).cities == 0 {
+            return vec![];
         }
-        possible_building_intersections
+        let mut cities: Vec<IntersectionId> = self.state.buildings.iter()
+            .filter(|building| building.player == player && building.kind == BuildingKind::Settlement)
+            .map(|building| building.intersection_id)
+            .collect();
+        cities.sort_by_key(|intersection| intersection.0);
+        cities
     }
 
 
     /// Determines all possible road paths a player can build on the board.
     ///
-    /// This function identifies potential road locations based on the current state of the board and the roads
-    /// already owned by the player. It ensures that new roads are connected to existing roads owned by the player
-    /// and do not conflict with existing roads owned by other players.
+    /// A road can be built on any free path that touches one of the player's buildings, or that
+    /// touches one of the player's roads at an intersection without an opponent's building.
+    /// Roads can branch from any intersection of the network, not only from its ends.
     ///
     /// # Arguments
     /// - `player`: The `Player` whose potential road paths are being evaluated.
@@ -166,11 +141,6 @@ impl Game {
     /// # Returns
     /// A `HashSet` containing all possible `Path`s where the player can build new roads.
     ///
-    /// # Methodology
-    /// 1. Builds a road graph of intersections connected by roads owned by the player.
-    /// 2. Identifies "leaf" intersections—intersections with exactly one connected road—where new roads can branch out.
-    /// 3. Collects all valid paths where a new road can connect to these leaf intersections.
-    ///
     /// # Example
     /// ```no_run
     /// let possible_paths = game.possible_road_paths(Player::Red);
@@ -180,24 +150,18 @@ impl Game {
     /// ```
     pub(crate) fn possible_road_paths(&self, player: Player) -> HashSet<Path> {
         let graph = self.road_graph(player);
-        let mut leaf_possible: HashSet<usize> = HashSet::new();
-        let mut leaf_already_made: HashSet<usize> = HashSet::new();
-        for key in graph.keys() {
-            let values = graph.get(key).unwrap();
-            if values.len() == 1 {
-                leaf_already_made.insert(values[0]);
-                leaf_possible.insert(*key);
+        let occupied: HashSet<usize> = self.state.roads.iter().map(|road| road.id.0).collect();
+        let connects = |intersection: &IntersectionId| {
+            match self.state.buildings.iter().find(|building| building.intersection_id == *intersection) {
+                Some(building) => building.player == player,
+                None => graph.contains_key(&intersection.0),
             }
-        }
+        };
 
         let mut possible_road_paths: HashSet<Path> = HashSet::new();
-        for path in self.board.paths.iter() {
-            let Path(IntersectionId(a), IntersectionId(b)) = path;
-            if leaf_possible.contains(a) && !leaf_already_made.contains(b) {
-                possible_road_paths.insert(path.clone());
-            }
-
-            if leaf_possible.contains(b) && !leaf_already_made.contains(a) {
+        for (id, path) in self.board.paths.iter().enumerate() {
+            let Path(a, b) = path;
+            if !occupied.contains(&id) && (connects(a) || connects(b)) {
                 possible_road_paths.insert(path.clone());
             }
         }
@@ -206,7 +170,8 @@ impl Game {
 
     /// Constructs a set of intersections that are too close to existing buildings.
     ///
-    /// Buildings cannot be placed adjacent to other buildings.
+    /// Buildings cannot be placed on or next to other buildings; the neighbours are taken
+    /// from the board topology with `Board::neighbours_of`.
     ///
     /// # Returns
     /// A `HashSet` of `IntersectionId`s that are too close to existing buildings.
@@ -218,14 +183,255 @@ impl Game {
                 ..
             } = building;
 
-            for path in  &self.board.intersections[intersection_id.0].paths {
-                let Path(intersection_a, intersection_b) = self.board.paths[path.0];
-                too_close_intersections.insert(intersection_a);
-                too_close_intersections.insert(intersection_b);
+            too_close_intersections.insert(*intersection_id);
+            too_close_intersections.extend(self.board.neighbours_of(*intersection_id));
+        }
+        too_close_intersections
+    }
 
+    /// Lists the intersections a settlement can be placed on in the setup phase.
+    ///
+    /// Setup settlements need no road; only the distance rule applies.
+    pub fn setup_intersections(&self) -> Vec<IntersectionId> {
+        let too_close = self.too_close_intersections();
+        (0..self.board.intersections.len())
+            .map(IntersectionId)
+            .filter(|intersection| !too_close.contains(intersection))
+            .collect()
+    }
+
+    /// Returns the player who has to act next, or `None` once the game is over.
+    ///
+    /// This is the player whose turn it is, except while players discard, answer a trade offer
+    /// or build after the turn, where it is the first player still waiting.
+    pub fn to_move(&self) -> Option<Player> {
+        match &self.state.turn.phase {
+            Phase::GameOver => None,
+            Phase::Discard { players } | Phase::SpecialBuilding { players } => players.first().copied(),
+            Phase::Trade { waiting, .. } => waiting.first().copied(),
+            _ => Some(self.state.turn.player),
+        }
+    }
+
+    /// Lists every action the given player may take in the current phase of the turn.
+    ///
+    /// Players who are not allowed to act right now get an empty list. While discarding, every
+    /// choice of cards is listed; robber moves are listed for every tile and steal target.
+    /// Trade offers and counter offers are not generated, only the answers to a pending offer.
+    ///
+    /// # Arguments
+    /// - `player`: The player whose actions are generated.
+    ///
+    /// # Returns
+    /// A `Vec` of actions that `apply` accepts for `player`.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// for action in game.possible_actions(Player::Red) {
+    ///     println!("{:?}", action);
+    /// }
+    /// ```
+    pub fn possible_actions(&self, player: Player) -> Vec<Action> {
+        let turn = &self.state.turn;
+        if let Phase::Discard { players } = &turn.phase {
+            if !players.contains(&player) {
+                return vec![];
             }
+            return self.discard_options(player).into_iter()
+                .map(|cards| Action::Discard { player, cards })
+                .collect();
+        }
+        if let Phase::Trade { offer, waiting } = &turn.phase {
+            let mut actions = vec![];
+            if waiting.contains(&player) {
+                if offer.can_accept(player, &self.state.resources) {
+                    actions.push(Action::AcceptTrade { player });
+                }
+                actions.push(Action::RejectTrade { player });
+            } else if offer.from == player {
+                actions.push(Action::RejectTrade { player });
+            }
+            return actions;
+        }
+        if let Phase::SpecialBuilding { players } = &turn.phase {
+            if players.first() != Some(&player) {
+                return vec![];
+            }
+            let mut actions = self.build_actions(player);
+            actions.push(Action::EndTurn { player });
+            return actions;
+        }
+        if turn.player != player {
+            return vec![];
+        }
+
+        let mut actions = vec![];
+        match turn.phase {
+            Phase::Setup { settlement: None, .. } => {
+                for intersection in self.setup_intersections() {
+                    actions.push(Action::BuildSettlement { player, intersection });
+                }
+            }
+            Phase::Setup { settlement: Some(settlement), .. } => {
+                for path in self.board.paths_of(settlement) {
+                    if !self.state.roads.iter().any(|road| road.id == *path) {
+                        actions.push(Action::BuildRoad { player, path: *path });
+                    }
+                }
+            }
+            Phase::Roll => {
+                actions.push(Action::RollDice { player });
+                actions.extend(self.development_card_actions(player));
+            }
+            Phase::MoveRobber => {
+                for robber in self.robber_placements() {
+                    let targets = self.steal_targets(player, robber);
+                    if targets.is_empty() {
+                        actions.push(Action::MoveRobber { player, robber, victim: None });
+                    }
+                    for victim in targets {
+                        actions.push(Action::MoveRobber { player, robber, victim: Some(victim) });
+                    }
+                }
+            }
+            Phase::Main => {
+                let resources = self.state.resources[player];
+                actions.extend(self.build_actions(player));
+                actions.extend(self.development_card_actions(player));
+                for give in RESOURCES.iter().filter(|give| resources[**give] >= self.trade_ratio(player, **give)) {
+                    for get in RESOURCES.iter().filter(|get| *get != give && self.state.bank.resources[**get] > 0) {
+                        actions.push(Action::TradeWithBank { player, give: *give, get: *get });
+                    }
+                }
+                actions.push(Action::EndTurn { player });
+            }
+            Phase::RoadBuilding { .. } => {
+                if self.remaining_pieces(player).roads > 0 {
+                    let possible_paths = self.possible_road_paths(player);
+                    for (id, path) in self.board.paths.iter().enumerate() {
+                        if possible_paths.contains(path) && !self.state.roads.iter().any(|road| road.id == PathId(id)) {
+                            actions.push(Action::BuildRoad { player, path: PathId(id) });
+                        }
+                    }
+                }
+            }
+            Phase::Discard { .. } | Phase::Trade { .. } | Phase::SpecialBuilding { .. } | Phase::GameOver => {}
+        }
+        actions
+    }
+
+    /// Lists the plays of the development cards `player` may play right now.
+    ///
+    /// Year of plenty is listed once per unordered pair of resources the bank can give.
+    fn development_card_actions(&self, player: Player) -> Vec<Action> {
+        let mut actions = vec![];
+        for card in self.playable_development_cards(player) {
+            match card {
+                DevelopmentCard::Knight => actions.push(Action::PlayKnight { player }),
+                DevelopmentCard::RoadBuilding => actions.push(Action::PlayRoadBuilding { player }),
+                DevelopmentCard::YearOfPlenty => {
+                    for (i, first) in RESOURCES.iter().enumerate() {
+                        for second in RESOURCES[i..].iter() {
+                            let cards = ResourceCount::single(*first, 1) + ResourceCount::single(*second, 1);
+                            if (self.state.bank.resources - cards).is_positive() {
+                                actions.push(Action::PlayYearOfPlenty { player, first: *first, second: *second });
+                            }
+                        }
+                    }
+                }
+                DevelopmentCard::Monopoly => {
+                    for resource in RESOURCES.iter() {
+                        actions.push(Action::PlayMonopoly { player, resource: *resource });
+                    }
+                }
+                DevelopmentCard::VictoryPoint => {}
+            }
+        }
+        actions
+    }
+
+    /// Lists the roads, settlements, cities and development cards `player` can pay for right now.
+    fn build_actions(&self, player: Player) -> Vec<Action> {
+        let mut actions = vec![];
+        let resources = self.state.resources[player];
+        let pieces = self.remaining_pieces(player);
+        if (resources - ROAD_COST).is_positive() && pieces.roads > 0 {
+            let possible_paths = self.possible_road_paths(player);
+            for (id, path) in self.board.paths.iter().enumerate() {
+                if possible_paths.contains(path) && !self.state.roads.iter().any(|road| road.id == PathId(id)) {
+                    actions.push(Action::BuildRoad { player, path: PathId(id) });
+                }
+            }
+        }
+        if (resources - SETTLEMENT_COST).is_positive() && pieces.settlements > 0 {
+            for intersection in self.legal_settlements(player) {
+                actions.push(Action::BuildSettlement { player, intersection });
+            }
+        }
+        if (resources - CITY_COST).is_positive() {
+            for intersection in self.legal_cities(player) {
+                actions.push(Action::BuildCity { player, intersection });
+            }
+        }
+        if (resources - DEVELOPMENT_COST).is_positive() && !self.state.bank.development.is_empty() {
+            actions.push(Action::BuyDevelopmentCard { player });
+        }
+        actions
+    }
+
+    /// Lists the tiles the robber can be moved to: every tile except the one it stands on.
+    pub fn robber_placements(&self) -> Vec<RobberId> {
+        (0..self.board.tiles.len())
+            .map(RobberId)
+            .filter(|robber| *robber != self.state.robber)
+            .collect()
+    }
+
+    /// Lists the opponents `player` may steal from after moving the robber to `robber`.
+    ///
+    /// These are the other players with a building on a corner of the tile who hold at least one card.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// for victim in game.steal_targets(Player::Red, RobberId(4)) {
+    ///     println!("Red can rob {:?}", victim);
+    /// }
+    /// ```
+    pub fn steal_targets(&self, player: Player, robber: RobberId) -> Vec<Player> {
+        let mut targets = vec![];
+        for building in &self.state.buildings {
+            let victim = building.player;
+            let on_tile = self.board.tiles_of(building.intersection_id).iter()
+                .any(|tile| tile.0 == robber.0);
+            if on_tile && victim != player && self.state.resources[victim].total() > 0 && !targets.contains(&victim) {
+                targets.push(victim);
+            }
+        }
+        targets
+    }
+
+    /// Lists every way `player` can discard half of their hand, rounded down.
+    pub fn discard_options(&self, player: Player) -> Vec<ResourceCount> {
+        let hand = self.state.resources[player];
+        let mut options = vec![];
+        Self::discard_options_dfs(hand, 0, ResourceCount::default(), hand.total() / 2, &mut options);
+        options
+    }
+
+    /// Chooses how many cards of `RESOURCES[index]` and the following resources to add to `cards`,
+    /// until `count` cards are chosen.
+    fn discard_options_dfs(hand: ResourceCount, index: usize, cards: ResourceCount, count: i32, options: &mut Vec<ResourceCount>) {
+        if index == RESOURCES.len() {
+            if cards.total() == count {
+                options.push(cards);
+            }
+            return;
+        }
+        let kind = RESOURCES[index];
+        let left = count - cards.total();
+        for amount in 0..=(hand[kind] as i32).min(left) {
+            Self::discard_options_dfs(hand, index + 1, cards + ResourceCount::single(kind, amount as i8), count, options);
         }
-        too_close_intersections
     }
 }
 
@@ -234,6 +440,8 @@ impl Game {
 #[cfg(test)] // Ensures the test code is compiled only in test mode
 mod tests {
     use super::*; // Import the functions from the parent module
+    use crate::game::{Building, BuildingKind, Road};
+    use crate::game::test_support::empty_game;
     use std::collections::HashSet;
     use std::convert::TryInto;
 
@@ -251,11 +459,58 @@ oo . oo . RS R oo . oo . oo . oo . oo . WS W oo . oo
      oo . oo . RS B oo . oo . oo . RS . oo . oo
           .   05B   .   06G   .   11W   .
           oo . oo . oo . oo . oo . oo . oo".to_string().try_into().unwrap();
-        let s: HashSet<Path> = vec![Path(IntersectionId(19), IntersectionId(20)), Path(IntersectionId(18), IntersectionId(29)), Path(IntersectionId(3), IntersectionId(4)), Path(IntersectionId(17), IntersectionId(18)), Path(IntersectionId(4), IntersectionId(12)), Path(IntersectionId(9), IntersectionId(19))].into_iter().collect();
+        let s: HashSet<Path> = vec![Path(IntersectionId(19), IntersectionId(20)), Path(IntersectionId(18), IntersectionId(29)), Path(IntersectionId(3), IntersectionId(4)), Path(IntersectionId(17), IntersectionId(18)), Path(IntersectionId(4), IntersectionId(12)), Path(IntersectionId(9), IntersectionId(19)),
+            Path(IntersectionId(14), IntersectionId(15)), Path(IntersectionId(22), IntersectionId(23)), Path(IntersectionId(24), IntersectionId(25)), Path(IntersectionId(34), IntersectionId(35)), Path(IntersectionId(36), IntersectionId(37)),
+            Path(IntersectionId(45), IntersectionId(46))].into_iter().collect();
         assert_eq!(s, game.possible_road_paths(Player::White));
     
     }
 
+    fn road_game(roads: &[(usize, Player)], buildings: &[(usize, Player)]) -> Game {
+        let mut game = empty_game(None);
+        for (path, player) in roads {
+            game.state.roads.push(Road { id: PathId(*path), player: *player });
+        }
+        for (intersection, player) in buildings {
+            game.state.buildings.push(Building { intersection_id: IntersectionId(*intersection), kind: BuildingKind::Settlement, player: *player });
+        }
+        game
+    }
+
+    fn path_ids(game: &Game, player: Player) -> HashSet<usize> {
+        let paths = game.possible_road_paths(player);
+        (0..game.board.paths.len()).filter(|id| paths.contains(&game.board.paths[*id])).collect()
+    }
+
+    #[test]
+    fn test_road_from_settlement_without_roads() {
+        // Intersection 10 connects paths 7, 12 and 13.
+        let game = road_game(&[], &[(10, Player::Red)]);
+        assert_eq!(path_ids(&game, Player::Red), vec![7, 12, 13].into_iter().collect());
+        assert!(path_ids(&game, Player::Blue).is_empty());
+    }
+
+    #[test]
+    fn test_road_branches_from_middle() {
+        // Red's road 0-1-2-3 may branch towards 10 at intersection 2, not only extend from its ends.
+        let game = road_game(&[(0, Player::Red), (1, Player::Red), (2, Player::Red)], &[]);
+        assert_eq!(path_ids(&game, Player::Red), vec![3, 6, 7].into_iter().collect());
+    }
+
+    #[test]
+    fn test_road_excludes_occupied_paths() {
+        let game = road_game(&[(0, Player::Red), (1, Player::Blue), (6, Player::Blue)], &[]);
+        assert!(path_ids(&game, Player::Red).is_empty());
+    }
+
+    #[test]
+    fn test_road_blocked_by_opponent_settlement() {
+        // A blue settlement on 2 cuts Red's road at its end; Blue's own settlement lets Blue build there.
+        let game = road_game(&[(0, Player::Red), (1, Player::Red)], &[(2, Player::Blue)]);
+        assert_eq!(path_ids(&game, Player::Red), vec![6].into_iter().collect());
+        assert_eq!(path_ids(&game, Player::Blue), vec![2, 7].into_iter().collect());
+    }
+
 
 
     #[test]
@@ -272,7 +527,59 @@ oo . oo . RS R oo . oo . oo . oo . oo . WS . oo . oo
      oo . oo . RS B oo . oo . oo . RS . oo . oo
           .   05B   .   06G   .   11W   .
           oo . oo . oo . oo . oo . oo . oo".to_string().try_into().unwrap();
-        assert_eq!(game.longest_road(Player::White), 7);
+        // White's road of 7 is broken in two by Blue's settlement on intersection 13.
+        assert_eq!(game.longest_road(Player::White), 4);
+    }
+
+    fn game_with_roads(paths: &[usize]) -> Game {
+        let mut game: Game = "
+          oo . oo . oo . oo . oo . oo . oo
+          .   10O   .   02W   .   09L   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   12G   .   06B   .   04W   .   10B   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   .   08O   .
+oo . oo . oo . oo . oo . oo . oo . oo . oo . oo . oo
+     .   08L   .   03O   .   04G   .   05W   .
+     oo . oo . oo . oo . oo . oo . oo . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo
+P  R  B  W".to_string().try_into().unwrap();
+        for path in paths {
+            game.state.roads.push(Road { id: PathId(*path), player: Player::Red });
+        }
+        game
+    }
+
+    #[test]
+    fn test_longest_road_loop() {
+        // The six roads around the top left tile.
+        let game = game_with_roads(&[0, 1, 6, 7, 11, 12]);
+        assert_eq!(game.longest_road(Player::Red), 6);
+
+        // A tail leading into the loop can be walked before going around it.
+        let game = game_with_roads(&[0, 1, 6, 7, 11, 12, 10, 18]);
+        assert_eq!(game.longest_road(Player::Red), 8);
+    }
+
+    #[test]
+    fn test_longest_road_figure_eight() {
+        // The roads around the two top left tiles, sharing one road.
+        let game = game_with_roads(&[0, 1, 6, 7, 11, 12, 2, 3, 8, 13, 14]);
+        assert_eq!(game.longest_road(Player::Red), 11);
+    }
+
+    #[test]
+    fn test_longest_road_broken_by_opponent() {
+        let mut game = game_with_roads(&[0, 1, 2, 3, 4]);
+        assert_eq!(game.longest_road(Player::Red), 5);
+
+        game.state.buildings.push(Building { intersection_id: IntersectionId(2), kind: BuildingKind::Settlement, player: Player::Red });
+        assert_eq!(game.longest_road(Player::Red), 5);
+
+        game.state.buildings[0].player = Player::Blue;
+        assert_eq!(game.longest_road(Player::Red), 3);
+        assert_eq!(game.longest_road(Player::Blue), 0);
     }
 
     #[test]
@@ -290,6 +597,56 @@ oo . oo . RS R oo . oo . oo . oo . oo . WS W oo . oo
           .   05B   .   06G   .   11W   .
           oo . oo . oo . oo . oo . oo . oo".to_string().try_into().unwrap();
         let s: HashSet<IntersectionId> = vec![IntersectionId(46), IntersectionId(6), IntersectionId(4), IntersectionId(5)].into_iter().collect();
-        assert_eq!(game.possible_building_intersections(Player::White), s);
+        assert_eq!(game.legal_settlements(Player::White).into_iter().collect::<HashSet<_>>(), s);
+    }
+
+    #[test]
+    fn test_legal_settlements() {
+        let game: Game = "
+          oo . oo . oo . oo . oo W oo W oo
+          .   10O   .   02W   .   09L   W
+     oo . oo . oo . RS R oo . oo B BS W oo . oo
+     .   12G   .   06B   .   04W   W   10B   .
+oo . oo . oo W WS . oo . oo . oo . oo W oo . oo . oo
+.   09G!  .   11L   .   00N   .   03L   W   08O   .
+oo . oo . RS R oo . oo . oo . oo . oo . WS W oo . oo
+     .   08L   .   03O   .   04G   B   05W   W
+     oo . oo . RS B oo . oo . oo . RS . oo . oo
+          .   05B   .   06G   .   11W   .
+          oo . oo . oo . oo . oo . oo . oo".to_string().try_into().unwrap();
+        assert_eq!(game.legal_settlements(Player::White), vec![IntersectionId(4), IntersectionId(5), IntersectionId(6), IntersectionId(46)]);
+        assert_eq!(game.legal_settlements(Player::Blue), vec![]);
+        assert_eq!(game.legal_cities(Player::White), vec![IntersectionId(19), IntersectionId(35)]);
+    }
+
+    #[test]
+    fn test_distance_rule_uses_topology() {
+        // The distance rule takes the neighbours of 45 from Board::neighbours_of, so 44, 46 and 53 are blocked.
+        let game = road_game(&[(53, Player::Red)], &[(45, Player::Blue)]);
+        assert_eq!(game.board.neighbours_of(IntersectionId(45)), vec![IntersectionId(44), IntersectionId(46), IntersectionId(53)]);
+        assert_eq!(game.legal_settlements(Player::Red), vec![IntersectionId(36)]);
+        assert!(game.setup_intersections().iter().all(|id| ![44, 45, 46, 53].contains(&id.0)));
+    }
+
+    #[test]
+    fn test_legal_cities_need_a_city_piece() {
+        let mut game = road_game(&[], &[(0, Player::Red), (20, Player::Red)]);
+        assert_eq!(game.legal_cities(Player::Red), vec![IntersectionId(0), IntersectionId(20)]);
+        for intersection in [4, 12, 30, 40] {
+            game.state.buildings.push(Building { intersection_id: IntersectionId(intersection), kind: BuildingKind::City, player: Player::Red });
+        }
+        assert!(game.legal_cities(Player::Red).is_empty());
+    }
+
+    #[test]
+    fn test_road_building_needs_a_road_piece() {
+        let mut game = road_game(&[(40, Player::Red)], &[]);
+        game.state.turn.phase = Phase::RoadBuilding { roads: 2 };
+        assert!(!game.possible_actions(Player::Red).is_empty());
+        for path in 41..55 {
+            game.state.roads.push(Road { id: PathId(path), player: Player::Red });
+        }
+        assert_eq!(game.remaining_pieces(Player::Red).roads, 0);
+        assert!(game.possible_actions(Player::Red).is_empty());
     }
 }
\ No newline at end of file
@@ -0,0 +1,188 @@
+use crate::game::{Action, Game, Phase, Player, RuleError};
+use crate::game::dice::Rng;
+use crate::moves::evaluation::Evaluator;
+use crate::moves::ismcts::Budget;
+
+/// How a bot chooses its moves.
+///
+/// - `Maximin`: Expectiminimax search looking `depth` actions ahead.
+/// - `Ismcts`: Information-set Monte Carlo tree search within `budget`.
+///
+/// Both score positions with their `evaluator`, so differently tuned weights can play each other.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Strategy {
+    Maximin { depth: usize, evaluator: Evaluator },
+    Ismcts { budget: Budget, evaluator: Evaluator },
+}
+
+impl Game {
+    /// Returns the action `strategy` chooses for `player`, or `None` if they have nothing to do.
+    pub fn choose_move(&self, player: Player, strategy: Strategy, seed: u64) -> Option<Action> {
+        match strategy {
+            Strategy::Maximin { depth, evaluator } => self.compute_best_move(player, depth, &evaluator),
+            Strategy::Ismcts { budget, evaluator } => self.compute_ismcts_move(player, budget, &evaluator, seed),
+        }
+    }
+
+    /// Lets bots play the game against each other.
+    ///
+    /// Whoever is to move asks their strategy for an action, until the game is over or
+    /// `max_actions` actions were played.
+    ///
+    /// # Arguments
+    /// - `strategy`: Returns the strategy of every player in the game.
+    /// - `max_actions`: The number of actions after which the game is abandoned.
+    /// - `seed`: Seeds the bots that need randomness.
+    ///
+    /// # Returns
+    /// The winner, or `None` if the game was abandoned or the player to move had no action.
+    ///
+    /// # Errors
+    /// Returns the `RuleError` of the first action a strategy chose that the rules reject.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let winner = game.self_play(|player| match player {
+    ///     Player::Red => Strategy::Ismcts { budget: Budget::Iterations(500), evaluator: Evaluator::default() },
+    ///     _ => Strategy::Maximin { depth: 2, evaluator: Evaluator::default() },
+    /// }, 2000, 7)?;
+    /// ```
+    pub fn self_play<F: Fn(Player) -> Strategy>(&mut self, strategy: F, max_actions: usize, seed: u64) -> Result<Option<Player>, RuleError> {
+        self.play_out(|game, player, seed| game.choose_move(player, strategy(player), seed), max_actions, seed)
+    }
+
+    /// Plays the game with `choose` picking the action of whoever is to move, seeded with
+    /// `seed` plus the number of actions played so far, as `self_play` describes.
+    fn play_out<F: Fn(&Game, Player, u64) -> Option<Action>>(&mut self, choose: F, max_actions: usize, seed: u64) -> Result<Option<Player>, RuleError> {
+        for turn in 0..max_actions {
+            let player = match self.to_move() {
+                Some(player) => player,
+                None => break,
+            };
+            match choose(self, player, seed.wrapping_add(turn as u64)) {
+                Some(action) => self.apply(action)?,
+                None => break,
+            };
+        }
+        if self.state.turn.phase == Phase::GameOver {
+            Ok(self.winner())
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Plays a series of games from this position between bots with different strategies.
+    ///
+    /// The strategies take the seats in turn: in game `g` the player in seat `i` plays
+    /// `strategies[(i + g) % strategies.len()]`, so every strategy starts from every seat. Every
+    /// game reseeds the dice and reshuffles the development deck with `seed + g`.
+    ///
+    /// # Arguments
+    /// - `strategies`: The strategies to compare.
+    /// - `games`: The number of games to play.
+    /// - `max_actions`: The number of actions after which a game is abandoned.
+    /// - `seed`: Seeds the first game.
+    ///
+    /// # Returns
+    /// The wins of every strategy, indexed like `strategies`, and the games nobody won.
+    ///
+    /// # Errors
+    /// Returns the `RuleError` of the first illegal action a strategy chose.
+    ///
+    /// # Panics
+    /// Panics if `strategies` is empty.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// let tally = game.play_match(&[
+    ///     Strategy::Maximin { depth: 2, evaluator: Evaluator::default() },
+    ///     Strategy::Ismcts { budget: Budget::Iterations(500), evaluator: Evaluator::default() },
+    /// ], 12, 2000, 7)?;
+    /// println!("Maximin {} : {} ISMCTS, {} abandoned", tally.wins[0], tally.wins[1], tally.abandoned);
+    /// ```
+    pub fn play_match(&self, strategies: &[Strategy], games: usize, max_actions: usize, seed: u64) -> Result<Tally, RuleError> {
+        assert!(!strategies.is_empty(), "A match needs at least one strategy");
+        let mut tally = Tally { wins: vec![0; strategies.len()], abandoned: 0 };
+        for g in 0..games {
+            let game_seed = seed.wrapping_add(g as u64);
+            let mut game = self.clone();
+            game.state.rng = Rng::new(game_seed);
+            game.state.shuffle_development_deck();
+            let index = |player: Player| {
+                let seat = self.state.players.iter().position(|other| *other == player).unwrap_or(0);
+                (seat + g) % strategies.len()
+            };
+            match game.self_play(|player| strategies[index(player)], max_actions, game_seed)? {
+                Some(winner) => tally.wins[index(winner)] += 1,
+                None => tally.abandoned += 1,
+            }
+        }
+        Ok(tally)
+    }
+}
+
+/// The results of a match between strategies.
+///
+/// - `wins`: The games won by every strategy, indexed like the strategies of the match.
+/// - `abandoned`: The games nobody won.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: Vec<usize>,
+    pub abandoned: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::standard_game;
+    use crate::game::IntersectionId;
+    use crate::game::resources::{CITY_COST, ResourceCount};
+
+    fn get_game() -> Game {
+        standard_game(None)
+    }
+
+    #[test]
+    fn test_self_play_between_bots() {
+        let mut game = get_game();
+        game.state.resources.red = CITY_COST + CITY_COST;
+        game.state.resources.blue = ResourceCount::default();
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        let strategy = |player| match player {
+            Player::Red => Strategy::Ismcts { budget: Budget::Iterations(100), evaluator: Evaluator::default() },
+            _ => Strategy::Maximin { depth: 1, evaluator: Evaluator::default() },
+        };
+        assert_eq!(game.self_play(strategy, 20, 5), Ok(Some(Player::Red)));
+        assert_eq!(game.state.turn.phase, Phase::GameOver);
+    }
+
+    #[test]
+    fn test_self_play_gives_up() {
+        let mut game = get_game();
+        let number = game.state.turn.number;
+        assert_eq!(game.self_play(|_| Strategy::Maximin { depth: 1, evaluator: Evaluator::default() }, 6, 0), Ok(None));
+        assert!(game.state.turn.number > number);
+    }
+
+    #[test]
+    fn test_self_play_reports_illegal_moves() {
+        let mut game = get_game();
+        game.state.turn.phase = Phase::Main;
+        // Red has no settlement on intersection 0 to upgrade.
+        let build_city = |_: &Game, player, _| Some(Action::BuildCity { player, intersection: IntersectionId(0) });
+        assert_eq!(game.play_out(build_city, 6, 0), Err(RuleError::IllegalCity));
+    }
+
+    #[test]
+    fn test_play_match_rotates_seats() {
+        let mut game = get_game();
+        game.state.resources.red = CITY_COST;
+        game.state.resources.blue = ResourceCount::default();
+        game.state.victory_target = game.victory_points(Player::Red).total() + 1;
+        let default = Strategy::Maximin { depth: 1, evaluator: Evaluator::default() };
+        let tuned = Strategy::Maximin { depth: 1, evaluator: Evaluator { production: 3.0, ..Evaluator::default() } };
+        // Only Red can win; Red sits in the first seat, which the strategies take in turn.
+        let tally = game.play_match(&[default, tuned], 3, 40, 5).unwrap();
+        assert_eq!(tally, Tally { wins: vec![2, 1], abandoned: 0 });
+    }
+}
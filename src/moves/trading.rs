@@ -0,0 +1,117 @@
+use crate::game::{Action, Game, Phase, Player};
+use crate::game::trade::TradeOffer;
+
+/// Offers are refused from players this close to the victory target.
+const LEADER_MARGIN: u8 = 2;
+
+impl Game {
+    /// Decides whether `player` should accept a trade offer.
+    ///
+    /// The offer is accepted when the player can pay for it, the player making it is not about to win
+    /// by their public victory points, and the trade does not leave the player able to buy less than
+    /// before (counting bank trades); among such trades, only those that do not shrink the hand or
+    /// that unlock a new buy are taken.
+    ///
+    /// # Arguments
+    /// - `player`: The player answering the offer.
+    /// - `offer`: The offer to evaluate.
+    pub fn evaluate_offer(&self, player: Player, offer: &TradeOffer) -> bool {
+        if !offer.can_accept(player, &self.state.resources) {
+            return false;
+        }
+        if self.victory_points(offer.from).public() + LEADER_MARGIN >= self.state.victory_target {
+            return false;
+        }
+        let ratios = self.trade_ratios(player);
+        let before = self.state.resources[player];
+        let after = before - offer.get + offer.give;
+        let buys_before = before.possible_buys_with_trades(ratios);
+        let buys_after = after.possible_buys_with_trades(ratios);
+        buys_after.is_superset(&buys_before)
+            && (after.total() >= before.total() || buys_after.len() > buys_before.len())
+    }
+
+    /// Returns the answer of `player` to the pending trade offer.
+    ///
+    /// # Returns
+    /// `AcceptTrade` or `RejectTrade`, or `None` if no offer is waiting for this player.
+    ///
+    /// Example usage:
+    /// ```no_run
+    /// if let Some(answer) = game.respond_to_offer(Player::Blue) {
+    ///     game.apply(answer)?;
+    /// }
+    /// ```
+    pub fn respond_to_offer(&self, player: Player) -> Option<Action> {
+        match &self.state.turn.phase {
+            Phase::Trade { offer, waiting } if waiting.contains(&player) => {
+                if self.evaluate_offer(player, offer) {
+                    Some(Action::AcceptTrade { player })
+                } else {
+                    Some(Action::RejectTrade { player })
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::crowded_game;
+    use crate::game::TileKind;
+    use crate::game::development::{DevelopmentCard, HeldCard};
+    use crate::game::resources::ResourceCount;
+
+    fn get_game() -> Game {
+        crowded_game(Some("   G  W  B  L  O\nW  0  0  0  0  0\nR  0  0  3  0  0\nB  1  1  0  1  2"))
+    }
+
+    fn offer(give: ResourceCount, get: ResourceCount) -> TradeOffer {
+        TradeOffer { from: Player::Red, to: Some(Player::Blue), give, get }
+    }
+
+    #[test]
+    fn test_evaluate_offer() {
+        let game = get_game();
+        // Brick for the second ore lets Blue build a settlement and keeps the development card.
+        let useful = offer(ResourceCount::single(TileKind::Brick, 1), ResourceCount::single(TileKind::Ore, 1));
+        assert!(game.evaluate_offer(Player::Blue, &useful));
+
+        // Giving up grain costs Blue the development card without unlocking anything.
+        let harmful = offer(ResourceCount::single(TileKind::Brick, 1), ResourceCount::single(TileKind::Grain, 1));
+        assert!(!game.evaluate_offer(Player::Blue, &harmful));
+
+        let unaffordable = offer(ResourceCount::single(TileKind::Brick, 1), ResourceCount::single(TileKind::Lumber, 2));
+        assert!(!game.evaluate_offer(Player::Blue, &unaffordable));
+    }
+
+    #[test]
+    fn test_respond_to_offer() {
+        let mut game = get_game();
+        game.state.turn.phase = Phase::Main;
+        let useful = offer(ResourceCount::single(TileKind::Brick, 1), ResourceCount::single(TileKind::Ore, 1));
+        assert_eq!(game.respond_to_offer(Player::Blue), None);
+        assert!(game.apply(Action::OfferTrade { offer: useful }).is_ok());
+        assert_eq!(game.respond_to_offer(Player::White), None);
+        assert_eq!(game.respond_to_offer(Player::Blue), Some(Action::AcceptTrade { player: Player::Blue }));
+
+        game.state.victory_target = 7;
+        assert_eq!(game.respond_to_offer(Player::Blue), Some(Action::RejectTrade { player: Player::Blue }));
+    }
+
+    #[test]
+    fn test_evaluate_offer_ignores_hidden_points() {
+        let mut game = get_game();
+        let useful = offer(ResourceCount::single(TileKind::Brick, 1), ResourceCount::single(TileKind::Ore, 1));
+        game.state.victory_target = 8;
+        assert!(game.evaluate_offer(Player::Blue, &useful));
+
+        // Blue cannot see Red's victory point card, so only Red's public points count.
+        game.state.development_cards.push(HeldCard { player: Player::Red, card: DevelopmentCard::VictoryPoint, bought_turn: 0 });
+        assert_eq!(game.victory_points(Player::Red).total() + LEADER_MARGIN, game.state.victory_target);
+        assert!(game.evaluate_offer(Player::Blue, &useful));
+    }
+}
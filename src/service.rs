@@ -0,0 +1,131 @@
+use std::convert::{TryFrom, TryInto};
+use std::time::Duration;
+use crate::game::Game;
+use crate::game::encoding::TURN_MARKER;
+use crate::moves::evaluation::Evaluator;
+use crate::moves::maximin::Deepening;
+
+/// The time the move search may use when the request does not say.
+pub const DEFAULT_BUDGET_MS: u64 = 50;
+
+/// The most time a request may give the move search.
+pub const MAX_BUDGET_MS: u64 = 1000;
+
+/// Returns the time the move search may use for a request asking for `budget_ms`, capped at
+/// `MAX_BUDGET_MS`.
+pub fn budget(budget_ms: Option<u64>) -> Duration {
+    Duration::from_millis(budget_ms.unwrap_or(DEFAULT_BUDGET_MS).min(MAX_BUDGET_MS))
+}
+
+/// Returns the evaluator of a request.
+///
+/// # Arguments
+/// - `weights`: The weights of the request, as `name=value` pairs separated by commas.
+/// - `config`: The weights configured for the service, one `name = value` line per weight.
+///
+/// # Returns
+/// The weights of the request if any, else the configured weights, else the default weights.
+///
+/// # Errors
+/// Returns an error if the weights used do not parse.
+pub fn evaluator(weights: Option<&str>, config: Option<&str>) -> Result<Evaluator, &'static str> {
+    match (weights, config) {
+        (Some(weights), _) => Evaluator::try_from(weights.replace(',', "\n").as_str()),
+        (None, Some(config)) => Evaluator::try_from(config),
+        (None, None) => Ok(Evaluator::default()),
+    }
+}
+
+/// Answers a posted game with the action chosen for the player to act and the depth the
+/// search completed, as `action: ...` and `depth: ...` lines.
+///
+/// The game must carry a turn line, as a board alone does not tell who is to act nor in
+/// which phase of the turn.
+///
+/// # Arguments
+/// - `body`: The game in the ASCII encoding.
+/// - `budget`: The time the search may use.
+/// - `evaluator`: Scores the positions at the search horizon.
+///
+/// # Errors
+/// Returns the text of a bad request answer if the game does not parse or has no turn line.
+///
+/// Example usage:
+/// ```no_run
+/// let answer = answer(&body, budget(Some(100)), &Evaluator::default())?;
+/// ```
+pub fn answer(body: &str, budget: Duration, evaluator: &Evaluator) -> Result<String, String> {
+    if !body.lines().any(|line| line.split_whitespace().next() == Some(TURN_MARKER)) {
+        return Err("Invalid game: The game has no turn line\n".to_string());
+    }
+    let game: Game = body.to_string().try_into().map_err(|error| format!("Invalid game: {}\n", error))?;
+    let result = match game.to_move() {
+        Some(player) => game.compute_best_move_within(player, budget, evaluator),
+        None => Deepening { action: None, depth: 0 },
+    };
+    let action = match result.action {
+        Some(action) => format!("{:?}", action),
+        None => "none".to_string(),
+    };
+    Ok(format!("action: {}\ndepth: {}\n", action, result.depth))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::test_support::STANDARD_BOARD;
+
+    #[test]
+    fn test_answer_searches_the_player_to_act() {
+        let body = format!("{}\nT  R  main  4  1  0\n   G  W  B  L  O\nR  3  0  0  0  2", STANDARD_BOARD);
+        let text = answer(&body, budget(Some(200)), &Evaluator::default()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].strip_prefix("action: ").is_some_and(|action| !action.is_empty()));
+        assert!(lines[1].strip_prefix("depth: ").and_then(|depth| depth.parse::<usize>().ok()).is_some());
+
+        let body = format!("{}\nT  W  roll  4  0  0", STANDARD_BOARD);
+        assert_eq!(answer(&body, budget(Some(10)), &Evaluator::default()).unwrap(),
+                   "action: RollDice { player: White }\ndepth: 0\n");
+    }
+
+    #[test]
+    fn test_answer_rejects_bad_games() {
+        assert_eq!(answer(STANDARD_BOARD, budget(None), &Evaluator::default()), Err("Invalid game: The game has no turn line\n".to_string()));
+        let body = format!("{}\nT  O  main  4  1  0", STANDARD_BOARD);
+        assert_eq!(answer(&body, budget(None), &Evaluator::default()), Err("Invalid game: The turn player is not in the game\n".to_string()));
+        let body = format!("{}\nT  R  main  4  1  0", STANDARD_BOARD.replace("09G!", "09G "));
+        assert_eq!(answer(&body, budget(None), &Evaluator::default()), Err("Invalid game: The board has no robber\n".to_string()));
+        let bad_turns = [
+            ("setup:6", "The setup step is past the end of the setup phase"),
+            ("setup:0:999", "Invalid setup settlement"),
+            ("setup:0:0", "The setup settlement is not a building of the turn player"),
+            ("discard:RO", "A player in the phase is not in the game"),
+            ("special:BO", "A player in the phase is not in the game"),
+            ("trade:R:O:1,0,0,0,0:0,1,0,0,0:B", "A player in the phase is not in the game"),
+            ("trade:R:-:1,0,0,0,0:0,1,0,0,0:BO", "A player in the phase is not in the game"),
+        ];
+        for (phase, error) in bad_turns.iter() {
+            let body = format!("{}\nT  R  {}  0  0  0", STANDARD_BOARD, phase);
+            assert_eq!(answer(&body, budget(None), &Evaluator::default()), Err(format!("Invalid game: {}\n", error)));
+        }
+        let body = format!("{}\nT  R  main  4  1  0\n   G  W  B  L  O\nR  -128  0  0  0  0", STANDARD_BOARD);
+        assert_eq!(answer(&body, budget(None), &Evaluator::default()), Err("Invalid game: Invalid resource count\n".to_string()));
+        let body = format!("{}\nT  R  main  4  1  0\n   G  W  B  L  O\nR  100  0  0  0  0\nB  100  0  0  0  0", STANDARD_BOARD);
+        assert_eq!(answer(&body, budget(None), &Evaluator::default()), Err("Invalid game: Invalid resource count\n".to_string()));
+    }
+
+    #[test]
+    fn test_budget_and_evaluator() {
+        assert_eq!(budget(None), Duration::from_millis(DEFAULT_BUDGET_MS));
+        assert_eq!(budget(Some(20)), Duration::from_millis(20));
+        assert_eq!(budget(Some(u64::MAX)), Duration::from_millis(MAX_BUDGET_MS));
+
+        assert_eq!(evaluator(None, None), Ok(Evaluator::default()));
+        assert_eq!(evaluator(None, Some("harbors = 2")), Ok(Evaluator { harbors: 2.0, ..Evaluator::default() }));
+        assert_eq!(evaluator(Some("harbors=3,win=10"), Some("harbors = 2")),
+                   Ok(Evaluator { harbors: 3.0, win: 10.0, ..Evaluator::default() }));
+        assert_eq!(evaluator(Some("ports=3"), None), Err("Unknown weight name"));
+    }
+}